@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+
+use kon_rs::{Band, BandMember};
+
+use crate::{InstrumentType, Member, MemberList};
+
+// バンドに必須のパート
+const REQUIRED_PARTS: [InstrumentType; 4] = [
+    InstrumentType::VOCAL,
+    InstrumentType::ELECTRIC_GUITAR.union(InstrumentType::ACOUSTIC_GUITAR),
+    InstrumentType::ELECTRIC_BASS,
+    InstrumentType::DRUMS,
+];
+
+// 入れられるなら入れるパート
+const OPTIONAL_PART: InstrumentType = InstrumentType::KEYBOARD.union(InstrumentType::PIANO);
+
+// バンドひとつあたりに調べる組み合わせの数の上限
+const SEARCH_LIMIT: usize = 1000;
+
+#[derive(Default)]
+struct LineupSearch {
+    // 調べた組み合わせの数
+    count: usize,
+
+    // 一緒に組みたいメンバーの組の数と組み合わせ
+    best: Option<(usize, Vec<(usize, InstrumentType)>)>,
+}
+
+/// 提案したバンドの組み合わせ
+pub struct Formation {
+    bands: Vec<Band>,
+    unassigned_members: Vec<String>,
+}
+
+impl Formation {
+    pub fn bands(&self) -> &[Band] {
+        &self.bands
+    }
+
+    /// どのバンドにも入らなかったメンバー
+    pub fn unassigned_members(&self) -> &[String] {
+        &self.unassigned_members
+    }
+}
+
+/// 参加者一覧からバンドの組み合わせを提案します
+///
+/// ボーカル、ギター、ベース、ドラムがそろったバンドをなるべく多く作り、キーボードは入れられるバンドに追加します。
+pub struct FormationRecommender<'a> {
+    member_list: &'a MemberList,
+
+    // メンバーが掛け持ちできるバンドの数
+    default_band_limit: usize,
+    band_limit_table: HashMap<String, usize>,
+
+    // 一緒に組みたいメンバーと組みたくないメンバーの組
+    wants: Vec<(String, String)>,
+    avoids: Vec<(String, String)>,
+}
+
+impl<'a> FormationRecommender<'a> {
+    pub fn new(member_list: &'a MemberList) -> Self {
+        Self {
+            member_list,
+            default_band_limit: 1,
+            band_limit_table: HashMap::default(),
+            wants: Vec::default(),
+            avoids: Vec::default(),
+        }
+    }
+
+    /// 指定しなかったメンバーが掛け持ちできるバンドの数
+    pub fn with_default_band_limit(mut self, limit: usize) -> Self {
+        self.default_band_limit = limit;
+        self
+    }
+
+    /// メンバーが掛け持ちできるバンドの数
+    pub fn with_band_limit(mut self, name: &str, limit: usize) -> Self {
+        self.band_limit_table.insert(name.to_string(), limit);
+        self
+    }
+
+    /// 一緒に組みたいメンバーの組を追加します。なるべく同じバンドにします
+    pub fn push_wants(mut self, lhs: &str, rhs: &str) -> Self {
+        self.wants.push((lhs.to_string(), rhs.to_string()));
+        self
+    }
+
+    /// 組みたくないメンバーの組を追加します。同じバンドにはしません
+    pub fn push_avoid(mut self, lhs: &str, rhs: &str) -> Self {
+        self.avoids.push((lhs.to_string(), rhs.to_string()));
+        self
+    }
+
+    pub fn recommend(&self) -> Formation {
+        let members = self.member_list.members();
+        let mut capacities: Vec<usize> = members
+            .iter()
+            .map(|member| {
+                *self
+                    .band_limit_table
+                    .get(member.name())
+                    .unwrap_or(&self.default_band_limit)
+            })
+            .collect();
+
+        // 必須のパートがそろう限りバンドを作る
+        let mut lineups: Vec<Vec<(usize, InstrumentType)>> = Vec::default();
+        while let Some(lineup) = self.find_lineup(members, &capacities) {
+            for (index, _) in &lineup {
+                capacities[*index] -= 1;
+            }
+            lineups.push(lineup);
+        }
+
+        // 余ったキーボードを入れられるバンドに入れる
+        for lineup in &mut lineups {
+            let candidate = self
+                .sort_candidates(members, &capacities, lineup, OPTIONAL_PART)
+                .into_iter()
+                .next();
+            if let Some(index) = candidate {
+                capacities[index] -= 1;
+                lineup.push((index, role(&members[index], OPTIONAL_PART)));
+            }
+        }
+
+        let bands = lineups
+            .iter()
+            .enumerate()
+            .map(|(band_index, lineup)| Band {
+                name: format!("バンド{}", band_index + 1),
+                members: lineup
+                    .iter()
+                    .map(|(index, instrument)| BandMember::new(members[*index].name(), *instrument))
+                    .collect(),
+            })
+            .collect();
+        let unassigned_members = members
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| lineups.iter().all(|x| x.iter().all(|(i, _)| i != index)))
+            .map(|(_, member)| member.name().to_string())
+            .collect();
+        Formation {
+            bands,
+            unassigned_members,
+        }
+    }
+
+    // 必須のパートがそろう組み合わせを探す
+    fn find_lineup(
+        &self,
+        members: &[Member],
+        capacities: &[usize],
+    ) -> Option<Vec<(usize, InstrumentType)>> {
+        // 演奏できる人が少ないパートから決める
+        let mut parts = REQUIRED_PARTS.to_vec();
+        parts.sort_by_key(|part| {
+            members
+                .iter()
+                .zip(capacities)
+                .filter(|(member, capacity)| {
+                    0 < **capacity && member.instruments().intersects(*part)
+                })
+                .count()
+        });
+
+        let mut search = LineupSearch::default();
+        self.find_lineup_recursive(
+            members,
+            capacities,
+            &parts,
+            &mut Vec::default(),
+            &mut search,
+        );
+        search.best.map(|(_, lineup)| lineup)
+    }
+
+    fn find_lineup_recursive(
+        &self,
+        members: &[Member],
+        capacities: &[usize],
+        parts: &[InstrumentType],
+        lineup: &mut Vec<(usize, InstrumentType)>,
+        search: &mut LineupSearch,
+    ) {
+        if SEARCH_LIMIT <= search.count {
+            return;
+        }
+
+        let Some((part, rest)) = parts.split_first() else {
+            // 一緒に組みたいメンバーの組が多い組み合わせを採用する
+            search.count += 1;
+            let wants = self.count_wants(members, lineup);
+            if search.best.as_ref().is_none_or(|(best, _)| *best < wants) {
+                search.best = Some((wants, lineup.clone()));
+            }
+            return;
+        };
+
+        for index in self.sort_candidates(members, capacities, lineup, *part) {
+            lineup.push((index, role(&members[index], *part)));
+            self.find_lineup_recursive(members, capacities, rest, lineup, search);
+            lineup.pop();
+        }
+    }
+
+    // 組み合わせに含まれる一緒に組みたいメンバーの組の数
+    fn count_wants(&self, members: &[Member], lineup: &[(usize, InstrumentType)]) -> usize {
+        self.wants
+            .iter()
+            .filter(|(lhs, rhs)| {
+                let contains = |name: &str| lineup.iter().any(|(i, _)| members[*i].name() == name);
+                contains(lhs) && contains(rhs)
+            })
+            .count()
+    }
+
+    // パートを担当できるメンバーを優先順に並べる
+    fn sort_candidates(
+        &self,
+        members: &[Member],
+        capacities: &[usize],
+        lineup: &[(usize, InstrumentType)],
+        part: InstrumentType,
+    ) -> Vec<usize> {
+        let mut candidates: Vec<usize> = (0..members.len())
+            .filter(|index| 0 < capacities[*index])
+            .filter(|index| members[*index].instruments().intersects(part))
+            .filter(|index| lineup.iter().all(|(other, _)| other != index))
+            .filter(|index| {
+                lineup.iter().all(|(other, _)| {
+                    !contains_pair(&self.avoids, members[*index].name(), members[*other].name())
+                })
+            })
+            .collect();
+
+        // 一緒に組みたいメンバーが多い人、ほかのパートを担当できない人を優先する
+        candidates.sort_by_key(|index| {
+            let wants = lineup
+                .iter()
+                .filter(|(other, _)| {
+                    contains_pair(&self.wants, members[*index].name(), members[*other].name())
+                })
+                .count();
+            let versatility = REQUIRED_PARTS
+                .iter()
+                .filter(|x| members[*index].instruments().intersects(**x))
+                .count();
+            (std::cmp::Reverse(wants), versatility)
+        });
+        candidates
+    }
+}
+
+// パートのうちメンバーが担当する楽器
+fn role(member: &Member, part: InstrumentType) -> InstrumentType {
+    let instruments = member.instruments() & part;
+    instruments.iter().next().unwrap_or(instruments)
+}
+
+fn contains_pair(pairs: &[(String, String)], lhs: &str, rhs: &str) -> bool {
+    pairs
+        .iter()
+        .any(|(x, y)| (x == lhs && y == rhs) || (x == rhs && y == lhs))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{InstrumentType, Member, MemberList};
+
+    use super::FormationRecommender;
+
+    fn create_member_list() -> MemberList {
+        MemberList::new(vec![
+            Member::new("vo_a", InstrumentType::VOCAL),
+            Member::new(
+                "vo_b",
+                InstrumentType::VOCAL | InstrumentType::ELECTRIC_GUITAR,
+            ),
+            Member::new("gt_a", InstrumentType::ELECTRIC_GUITAR),
+            Member::new("gt_b", InstrumentType::ACOUSTIC_GUITAR),
+            Member::new("ba_a", InstrumentType::ELECTRIC_BASS),
+            Member::new("ba_b", InstrumentType::ELECTRIC_BASS),
+            Member::new("dr_a", InstrumentType::DRUMS),
+            Member::new("dr_b", InstrumentType::DRUMS),
+            Member::new("key_a", InstrumentType::KEYBOARD),
+        ])
+    }
+
+    #[test]
+    fn complete_bands() {
+        let member_list = create_member_list();
+        let formation = FormationRecommender::new(&member_list).recommend();
+
+        // 全員ひとつずつなので 2 バンド
+        assert_eq!(formation.bands().len(), 2);
+        for band in formation.bands() {
+            let instruments = band.instruments();
+            assert!(instruments.contains(InstrumentType::VOCAL));
+            assert!(instruments
+                .intersects(InstrumentType::ELECTRIC_GUITAR | InstrumentType::ACOUSTIC_GUITAR));
+            assert!(instruments.contains(InstrumentType::ELECTRIC_BASS));
+            assert!(instruments.contains(InstrumentType::DRUMS));
+        }
+
+        // キーボードはどちらかに入る
+        let keyboard_count = formation
+            .bands()
+            .iter()
+            .filter(|band| band.member_instrument("key_a").is_some())
+            .count();
+        assert_eq!(keyboard_count, 1);
+        assert!(formation.unassigned_members().is_empty());
+
+        // アコースティックギターで入る
+        let band = formation
+            .bands()
+            .iter()
+            .find(|band| band.member_instrument("gt_b").is_some())
+            .unwrap();
+        assert_eq!(
+            band.member_instrument("gt_b").unwrap().bits(),
+            InstrumentType::ACOUSTIC_GUITAR.bits()
+        );
+    }
+
+    #[test]
+    fn band_limit() {
+        let member_list = create_member_list();
+
+        // ドラムが掛け持ちできない
+        let formation = FormationRecommender::new(&member_list)
+            .with_default_band_limit(2)
+            .with_band_limit("dr_a", 1)
+            .with_band_limit("dr_b", 1)
+            .recommend();
+        assert_eq!(formation.bands().len(), 2);
+
+        // ドラムも掛け持ちできると 4 バンド
+        let formation = FormationRecommender::new(&member_list)
+            .with_default_band_limit(2)
+            .recommend();
+        assert_eq!(formation.bands().len(), 4);
+    }
+
+    #[test]
+    fn preference() {
+        let member_list = create_member_list();
+        let formation = FormationRecommender::new(&member_list)
+            .push_wants("vo_a", "dr_b")
+            .push_avoid("ba_a", "dr_b")
+            .recommend();
+        assert_eq!(formation.bands().len(), 2);
+
+        let band = formation
+            .bands()
+            .iter()
+            .find(|band| band.member_instrument("vo_a").is_some())
+            .unwrap();
+        assert!(band.member_instrument("dr_b").is_some());
+        assert!(band.member_instrument("ba_a").is_none());
+    }
+}
@@ -1,70 +1,46 @@
 pub mod clients;
+mod formation;
+mod lineup;
 mod member_list;
+mod roster;
 
-use kon_rs::InstrumentType;
+pub use formation::{Formation, FormationRecommender};
+use kon_rs::{algorithm::LocalDateTime, InstrumentType};
+pub use lineup::{
+    check_band_lineup, check_band_lineups, check_lineup, check_lineups, LineupWarning,
+};
 pub use member_list::MemberList;
+pub use roster::{parse_roster, Roster, RosterError, RowDiagnostic, Strictness};
 use std::collections::HashMap;
 
-#[derive(Debug, serde::Deserialize)]
-pub struct Record {
-    name: String,
-    property_name: String,
-    value: String,
-}
-
+/// 参加者一覧を読み取ります
+///
+/// 読み取れない行は飛ばしてログに出力します。メンバーは最初に現れた順に並びます。
 pub fn deserialize(str: &str) -> Vec<Member> {
-    let mut rdr = csv::Reader::from_reader(str.as_bytes());
-    let records = rdr.deserialize::<Record>().map(|x| x.unwrap());
-    let mut table = HashMap::<String, Member>::default();
-    for record in records {
-        if !table.contains_key(&record.name) {
-            table.insert(
-                record.name.clone(),
-                Member {
-                    name: record.name.clone(),
-                    instruments: Default::default(),
-                },
-            );
-        }
-
-        match &record.property_name as &str {
-            "instrument" => {
-                match &record.value as &str {
-                    "Vocal" => {
-                        table.get_mut(&record.name).unwrap().instruments |= InstrumentType::VOCAL
-                    }
-                    "ElectricBass" => {
-                        table.get_mut(&record.name).unwrap().instruments |=
-                            InstrumentType::ELECTRIC_BASS
-                    }
-                    "Keyboard" => {
-                        table.get_mut(&record.name).unwrap().instruments |= InstrumentType::KEYBOARD
-                    }
-                    "ElectricGuitar" => {
-                        table.get_mut(&record.name).unwrap().instruments |=
-                            InstrumentType::ELECTRIC_GUITAR
-                    }
-                    "Drums" => {
-                        table.get_mut(&record.name).unwrap().instruments |= InstrumentType::DRUMS
-                    }
-                    _ => {}
-                };
+    match parse_roster(str, Strictness::Lenient) {
+        Ok(roster) => {
+            for diagnostic in roster.diagnostics() {
+                log::warn!("{}", diagnostic);
             }
-            _ => {}
+            roster.into_members()
+        }
+        Err(error) => {
+            log::warn!("{}", error);
+            Vec::default()
         }
     }
-
-    let mut members = Vec::new();
-    for member in table.values() {
-        members.push(member.clone());
-    }
-    members
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct Member {
     name: String,
     instruments: InstrumentType,
+
+    // "Other:ウクレレ" のように書いた一覧にない楽器の名前
+    custom_instruments: Vec<String>,
+
+    // instrument 以外のプロパティ名 -> 値
+    properties: HashMap<String, String>,
 }
 
 impl Member {
@@ -72,9 +48,18 @@ impl Member {
         Self {
             name: name.to_string(),
             instruments,
+            custom_instruments: Vec::default(),
+            properties: HashMap::default(),
         }
     }
 
+    /// プロパティを設定します
+    pub fn with_property(mut self, property_name: &str, value: &str) -> Self {
+        self.properties
+            .insert(property_name.to_string(), value.to_string());
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -82,4 +67,123 @@ impl Member {
     pub fn instruments(&self) -> InstrumentType {
         self.instruments
     }
+
+    /// 一覧にない楽器の名前。instruments には InstrumentType::OTHER として含まれます
+    pub fn custom_instruments(&self) -> &[String] {
+        &self.custom_instruments
+    }
+
+    /// instrument 以外のプロパティ
+    pub fn property(&self, property_name: &str) -> Option<&str> {
+        self.properties.get(property_name).map(|x| x.as_str())
+    }
+
+    pub fn properties(&self) -> &HashMap<String, String> {
+        &self.properties
+    }
+
+    /// 表示名 (display_name)。なければ name
+    pub fn display_name(&self) -> &str {
+        self.property("display_name").unwrap_or(&self.name)
+    }
+
+    /// 学年 (grade)
+    pub fn grade(&self) -> Option<u32> {
+        self.property("grade")?.trim().parse().ok()
+    }
+
+    /// 学部 (faculty)
+    pub fn faculty(&self) -> Option<&str> {
+        self.property("faculty")
+    }
+
+    /// 連絡先 (contact)
+    pub fn contact(&self) -> Option<&str> {
+        self.property("contact")
+    }
+
+    /// 活動中か (active)。指定がなければ活動中として扱います
+    pub fn is_active(&self) -> bool {
+        match self.property("active").map(|x| x.trim().to_lowercase()) {
+            Some(value) => !matches!(value.as_str(), "false" | "no" | "0"),
+            None => true,
+        }
+    }
+
+    /// 入部日 (joined)。"2024-04-01" や "2024/04/01" の形式で指定します
+    pub fn joined_date(&self) -> Option<LocalDateTime> {
+        let value = self.property("joined")?;
+        let mut items = value.trim().split(['-', '/']).map(|x| x.parse::<u16>());
+        let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day)), None) =
+            (items.next(), items.next(), items.next(), items.next())
+        else {
+            return None;
+        };
+        let (Ok(month), Ok(day)) = (u8::try_from(month), u8::try_from(day)) else {
+            return None;
+        };
+        LocalDateTime::try_new(year, month, day, 0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kon_rs::algorithm::LocalDateTime;
+
+    use crate::{InstrumentType, Member};
+
+    #[test]
+    fn deserialize_properties() {
+        let data = "name,property_name,value\n\
+                    a,instrument,Vocal\n\
+                    a,grade,2\n\
+                    a,faculty,工学部\n\
+                    a,display_name,エー\n\
+                    a,joined,2023/04/01\n\
+                    a,active,false\n\
+                    b,instrument,Drums\n\
+                    b,contact,@b\n";
+        let mut members = crate::deserialize(data);
+        members.sort_by(|x, y| x.name().cmp(y.name()));
+
+        let a = &members[0];
+        assert_eq!(a.instruments().bits(), InstrumentType::VOCAL.bits());
+        assert_eq!(a.grade(), Some(2));
+        assert_eq!(a.faculty(), Some("工学部"));
+        assert_eq!(a.display_name(), "エー");
+        assert_eq!(a.joined_date(), Some(LocalDateTime::new(2023, 4, 1, 0, 0)));
+        assert!(!a.is_active());
+        assert_eq!(a.contact(), None);
+
+        let b = &members[1];
+        assert_eq!(b.display_name(), "b");
+        assert_eq!(b.contact(), Some("@b"));
+        assert_eq!(b.grade(), None);
+        assert!(b.is_active());
+        assert_eq!(b.property("instrument"), None);
+    }
+
+    #[test]
+    fn joined_date() {
+        let joined_date = |value: &str| {
+            Member::default()
+                .with_property("joined", value)
+                .joined_date()
+        };
+        assert_eq!(
+            joined_date("2024-02-29"),
+            Some(LocalDateTime::new(2024, 2, 29, 0, 0))
+        );
+        assert_eq!(
+            joined_date("2000/02/29"),
+            Some(LocalDateTime::new(2000, 2, 29, 0, 0))
+        );
+
+        // 存在しない日付
+        assert_eq!(joined_date("2024-02-31"), None);
+        assert_eq!(joined_date("2023-02-29"), None);
+        assert_eq!(joined_date("1900-02-29"), None);
+        assert_eq!(joined_date("2024-04-31"), None);
+        assert_eq!(joined_date("2024-13-01"), None);
+    }
 }
@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use kon_rs::Band;
+
+use crate::{InstrumentType, Member, MemberList};
+
+// バンドの標準的なパートと表示名
+const STANDARD_PARTS: [(InstrumentType, &str); 4] = [
+    (InstrumentType::VOCAL, "vocal"),
+    (
+        InstrumentType::ELECTRIC_GUITAR.union(InstrumentType::ACOUSTIC_GUITAR),
+        "guitar",
+    ),
+    (InstrumentType::ELECTRIC_BASS, "bass"),
+    (InstrumentType::DRUMS, "drums"),
+];
+
+// ふつうはバンドにひとりだけのパート
+const SINGLE_PARTS: [(InstrumentType, &str); 2] = [
+    (InstrumentType::ELECTRIC_BASS, "bass"),
+    (InstrumentType::DRUMS, "drums"),
+];
+
+/// バンドの編成について確認してほしいこと
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineupWarning {
+    /// 標準的なパートを担当できるメンバーがいない
+    MissingPart {
+        band_name: String,
+        part: &'static str,
+    },
+
+    /// ひとりだけのはずのパートしか担当できないメンバーが複数いる
+    DuplicatePart {
+        band_name: String,
+        part: &'static str,
+        count: usize,
+    },
+
+    /// 参加者一覧に登録されていないメンバー
+    UnknownMember { band_name: String, member: String },
+}
+
+impl LineupWarning {
+    pub fn band_name(&self) -> &str {
+        match self {
+            LineupWarning::MissingPart { band_name, .. }
+            | LineupWarning::DuplicatePart { band_name, .. }
+            | LineupWarning::UnknownMember { band_name, .. } => band_name,
+        }
+    }
+}
+
+impl fmt::Display for LineupWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineupWarning::MissingPart { band_name, part } => {
+                write!(f, "{band_name} has no {part} player")
+            }
+            LineupWarning::DuplicatePart {
+                band_name,
+                part,
+                count,
+            } => write!(f, "{band_name} has {count} {part} players"),
+            LineupWarning::UnknownMember { band_name, member } => {
+                write!(f, "{member} in {band_name} is not on the member list")
+            }
+        }
+    }
+}
+
+/// バンドのメンバーの担当できる楽器から、編成の過不足を調べます
+///
+/// ひとりがひとつのパートを担当するとして、標準的なパート (ボーカル、ギター、ベース、ドラム) が埋まるかを調べます。
+pub fn check_lineup<'a, T>(
+    band_name: &str,
+    member_ids: T,
+    member_list: &MemberList,
+) -> Vec<LineupWarning>
+where
+    T: IntoIterator<Item = &'a str>,
+{
+    let mut warnings = Vec::default();
+    let mut instruments: Vec<InstrumentType> = Vec::default();
+    for member_id in member_ids {
+        match find_member(member_list, member_id) {
+            Some(member) => instruments.push(member.instruments()),
+            None => warnings.push(LineupWarning::UnknownMember {
+                band_name: band_name.to_string(),
+                member: member_id.to_string(),
+            }),
+        }
+    }
+
+    warnings.extend(check_parts(band_name, &instruments));
+    warnings
+}
+
+/// バンドの担当楽器から、編成の過不足を調べます
+///
+/// 担当楽器が決まっていないメンバーは、参加者一覧の演奏できる楽器で調べます。
+pub fn check_band_lineup(band: &Band, member_list: &MemberList) -> Vec<LineupWarning> {
+    let mut warnings = Vec::default();
+    let mut instruments: Vec<InstrumentType> = Vec::default();
+    for band_member in &band.members {
+        let member = find_member(member_list, &band_member.id);
+        if member.is_none() {
+            warnings.push(LineupWarning::UnknownMember {
+                band_name: band.name.clone(),
+                member: band_member.id.clone(),
+            });
+        }
+
+        if !band_member.instrument.is_empty() {
+            instruments.push(band_member.instrument);
+        } else if let Some(member) = member {
+            instruments.push(member.instruments());
+        }
+    }
+
+    warnings.extend(check_parts(&band.name, &instruments));
+    warnings
+}
+
+/// すべてのバンドの編成を調べます。バンドはバンド名順に調べます
+pub fn check_lineups(
+    band_table: &HashMap<String, Vec<String>>,
+    member_list: &MemberList,
+) -> Vec<LineupWarning> {
+    let mut band_names: Vec<&String> = band_table.keys().collect();
+    band_names.sort();
+    band_names
+        .into_iter()
+        .flat_map(|band_name| {
+            let member_ids = band_table[band_name].iter().map(|x| x.as_str());
+            check_lineup(band_name, member_ids, member_list)
+        })
+        .collect()
+}
+
+/// すべてのバンドの編成を担当楽器から調べます。バンドはバンド名順に調べます
+pub fn check_band_lineups(bands: &[Band], member_list: &MemberList) -> Vec<LineupWarning> {
+    let mut bands: Vec<&Band> = bands.iter().collect();
+    bands.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+    bands
+        .into_iter()
+        .flat_map(|band| check_band_lineup(band, member_list))
+        .collect()
+}
+
+fn find_member<'a>(member_list: &'a MemberList, member_id: &str) -> Option<&'a Member> {
+    member_list
+        .members()
+        .iter()
+        .find(|member| member.name() == member_id)
+}
+
+// メンバーごとの担当できる楽器から、埋まらないパートとひとりだけのはずのパートの重複を調べる
+fn check_parts(band_name: &str, instruments: &[InstrumentType]) -> Vec<LineupWarning> {
+    let mut warnings = Vec::default();
+
+    // 埋められるパートが最も多い割り当てで、埋まらなかったパート
+    let mut assigned = vec![false; instruments.len()];
+    let covered = cover_parts(instruments, &STANDARD_PARTS, &mut assigned);
+    for (index, (_, part)) in STANDARD_PARTS.iter().enumerate() {
+        if !covered[index] {
+            warnings.push(LineupWarning::MissingPart {
+                band_name: band_name.to_string(),
+                part,
+            });
+        }
+    }
+
+    for (instrument, part) in SINGLE_PARTS {
+        let count = instruments
+            .iter()
+            .filter(|instruments| !instruments.is_empty() && instrument.contains(**instruments))
+            .count();
+        if 1 < count {
+            warnings.push(LineupWarning::DuplicatePart {
+                band_name: band_name.to_string(),
+                part,
+                count,
+            });
+        }
+    }
+
+    warnings
+}
+
+// 各パートが埋まったかどうか。埋まるパートが最も多くなるように割り当てる
+fn cover_parts(
+    instruments: &[InstrumentType],
+    parts: &[(InstrumentType, &str)],
+    assigned: &mut [bool],
+) -> Vec<bool> {
+    let Some(((instrument, _), rest)) = parts.split_first() else {
+        return Vec::default();
+    };
+
+    let count = |x: &[bool]| x.iter().filter(|x| **x).count();
+    let mut best: Option<Vec<bool>> = None;
+    for index in 0..instruments.len() {
+        if assigned[index] || !instruments[index].intersects(*instrument) {
+            continue;
+        }
+
+        assigned[index] = true;
+        let mut covered = vec![true];
+        covered.extend(cover_parts(instruments, rest, assigned));
+        assigned[index] = false;
+
+        if best
+            .as_ref()
+            .is_none_or(|best| count(best) < count(&covered))
+        {
+            best = Some(covered);
+        }
+    }
+
+    // このパートを空けたほうが多く埋まるなら空ける
+    let mut skipped = vec![false];
+    skipped.extend(cover_parts(instruments, rest, assigned));
+    match best {
+        Some(best) if count(&skipped) <= count(&best) => best,
+        _ => skipped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use kon_rs::{Band, BandMember};
+
+    use crate::{InstrumentType, Member, MemberList};
+
+    use super::{check_band_lineups, check_lineup, check_lineups, LineupWarning};
+
+    fn create_member_list() -> MemberList {
+        MemberList::new(vec![
+            Member::new("vo", InstrumentType::VOCAL),
+            Member::new(
+                "vo_gt",
+                InstrumentType::VOCAL | InstrumentType::ELECTRIC_GUITAR,
+            ),
+            Member::new("ba", InstrumentType::ELECTRIC_BASS),
+            Member::new("dr_a", InstrumentType::DRUMS),
+            Member::new("dr_b", InstrumentType::DRUMS),
+        ])
+    }
+
+    #[test]
+    fn complete() {
+        let member_list = create_member_list();
+        let warnings = check_lineup("band", ["vo", "vo_gt", "ba", "dr_a"], &member_list);
+        assert!(warnings.is_empty());
+    }
+
+    // ボーカルとギターをひとりで兼ねることはできない
+    #[test]
+    fn missing_part() {
+        let member_list = create_member_list();
+        let warnings = check_lineup("band", ["vo_gt", "ba", "dr_a"], &member_list);
+        assert_eq!(
+            warnings,
+            vec![LineupWarning::MissingPart {
+                band_name: "band".to_string(),
+                part: "guitar",
+            }]
+        );
+    }
+
+    #[test]
+    fn duplicate_drums() {
+        let member_list = create_member_list();
+        let warnings = check_lineup("band", ["vo", "vo_gt", "dr_a", "dr_b"], &member_list);
+        assert_eq!(
+            warnings,
+            vec![
+                LineupWarning::MissingPart {
+                    band_name: "band".to_string(),
+                    part: "bass",
+                },
+                LineupWarning::DuplicatePart {
+                    band_name: "band".to_string(),
+                    part: "drums",
+                    count: 2,
+                },
+            ]
+        );
+        assert_eq!(warnings[0].to_string(), "band has no bass player");
+        assert_eq!(warnings[1].to_string(), "band has 2 drums players");
+    }
+
+    #[test]
+    fn unknown_member() {
+        let member_list = create_member_list();
+        let band_table = HashMap::from([
+            (
+                "band_b".to_string(),
+                vec!["vo".to_string(), "vo_gt".to_string(), "ba".to_string()],
+            ),
+            (
+                "band_a".to_string(),
+                vec![
+                    "vo".to_string(),
+                    "vo_gt".to_string(),
+                    "ba".to_string(),
+                    "dr_a".to_string(),
+                    "unknown".to_string(),
+                ],
+            ),
+        ]);
+        let warnings = check_lineups(&band_table, &member_list);
+        assert_eq!(
+            warnings,
+            vec![
+                LineupWarning::UnknownMember {
+                    band_name: "band_a".to_string(),
+                    member: "unknown".to_string(),
+                },
+                LineupWarning::MissingPart {
+                    band_name: "band_b".to_string(),
+                    part: "drums",
+                },
+            ]
+        );
+    }
+
+    // 担当楽器が決まっていれば担当楽器で調べる
+    #[test]
+    fn band_roles() {
+        let member_list = create_member_list();
+        let bands = vec![
+            // vo_gt がボーカルを担当するのでギターがいない
+            Band {
+                name: "band_b".to_string(),
+                members: vec![
+                    BandMember::new("vo_gt", InstrumentType::VOCAL),
+                    BandMember::new("ba", InstrumentType::ELECTRIC_BASS),
+                    BandMember::new("dr_a", InstrumentType::DRUMS),
+                ],
+            },
+            // 担当楽器が決まっていない vo は参加者一覧のボーカルで調べる
+            Band {
+                name: "band_a".to_string(),
+                members: vec![
+                    BandMember::new("vo", InstrumentType::empty()),
+                    BandMember::new("vo_gt", InstrumentType::ELECTRIC_GUITAR),
+                    BandMember::new("ba", InstrumentType::ELECTRIC_BASS),
+                    BandMember::new("guest", InstrumentType::DRUMS),
+                ],
+            },
+        ];
+        let warnings = check_band_lineups(&bands, &member_list);
+        assert_eq!(
+            warnings,
+            vec![
+                LineupWarning::UnknownMember {
+                    band_name: "band_a".to_string(),
+                    member: "guest".to_string(),
+                },
+                LineupWarning::MissingPart {
+                    band_name: "band_b".to_string(),
+                    part: "guitar",
+                },
+            ]
+        );
+    }
+}
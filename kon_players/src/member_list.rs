@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use crate::{InstrumentType, Member};
 
 pub struct MemberList {
@@ -7,6 +9,13 @@ pub struct MemberList {
 }
 
 impl MemberList {
+    pub fn new(members: Vec<Member>) -> Self {
+        Self {
+            members,
+            instrument_filter: Default::default(),
+        }
+    }
+
     pub fn from_csv(data: &str) -> Self {
         let members = crate::deserialize(data);
         Self {
@@ -19,6 +28,31 @@ impl MemberList {
         &self.members
     }
 
+    /// プロパティの値が一致するメンバー
+    pub fn members_with_property<'a>(
+        &'a self,
+        property_name: &'a str,
+        value: &'a str,
+    ) -> impl Iterator<Item = &'a Member> {
+        self.members
+            .iter()
+            .filter(move |member| member.property(property_name) == Some(value))
+    }
+
+    /// プロパティの値ごとにメンバーをまとめます
+    ///
+    /// 値の順に並べます。プロパティを持たないメンバーは含みません。
+    pub fn group_by_property(&self, property_name: &str) -> BTreeMap<&str, Vec<&Member>> {
+        let mut groups: BTreeMap<&str, Vec<&Member>> = BTreeMap::default();
+        for member in &self.members {
+            let Some(value) = member.property(property_name) else {
+                continue;
+            };
+            groups.entry(value).or_default().push(member);
+        }
+        groups
+    }
+
     pub fn filter(&self) -> InstrumentType {
         self.instrument_filter
     }
@@ -35,3 +69,40 @@ impl MemberList {
         self.instrument_filter = self.instrument_filter.intersection(Default::default());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{InstrumentType, Member};
+
+    use super::MemberList;
+
+    fn create_member_list() -> MemberList {
+        MemberList::new(vec![
+            Member::new("a", InstrumentType::VOCAL).with_property("grade", "2"),
+            Member::new("b", InstrumentType::DRUMS).with_property("grade", "1"),
+            Member::new("c", InstrumentType::ELECTRIC_BASS).with_property("grade", "2"),
+            Member::new("d", InstrumentType::KEYBOARD),
+        ])
+    }
+
+    #[test]
+    fn members_with_property() {
+        let member_list = create_member_list();
+        let names: Vec<&str> = member_list
+            .members_with_property("grade", "2")
+            .map(|x| x.name())
+            .collect();
+        assert_eq!(names, vec!["a", "c"]);
+    }
+
+    #[test]
+    fn group_by_property() {
+        let member_list = create_member_list();
+        let groups: Vec<(&str, Vec<&str>)> = member_list
+            .group_by_property("grade")
+            .into_iter()
+            .map(|(value, members)| (value, members.iter().map(|x| x.name()).collect()))
+            .collect();
+        assert_eq!(groups, vec![("1", vec!["b"]), ("2", vec!["a", "c"])]);
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use kon_rs::parse_instruments;
+
+use crate::{InstrumentType, Member};
+
+// 参加者一覧の CSV の列
+const COLUMNS: [&str; 3] = ["name", "property_name", "value"];
+
+/// 読み取れない行の扱い
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Strictness {
+    /// 読み取れない行を飛ばして残りを読み取ります
+    #[default]
+    Lenient,
+
+    /// 読み取れない行がひとつでもあれば失敗します
+    Strict,
+}
+
+/// 読み取れなかった行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowDiagnostic {
+    /// 1 始まりの行番号。ヘッダーが 1 行目です
+    pub line: u64,
+
+    /// 問題のある列。行全体の問題なら None
+    pub field: Option<String>,
+
+    pub message: String,
+}
+
+impl fmt::Display for RowDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "line {} ({}): {}", self.line, field, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+/// 参加者一覧を読み取れなかった理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RosterError {
+    diagnostics: Vec<RowDiagnostic>,
+}
+
+impl RosterError {
+    pub fn diagnostics(&self) -> &[RowDiagnostic] {
+        &self.diagnostics
+    }
+}
+
+impl fmt::Display for RosterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self.diagnostics.iter().map(|x| x.to_string()).collect();
+        write!(f, "invalid roster: {}", messages.join(", "))
+    }
+}
+
+impl std::error::Error for RosterError {}
+
+/// 読み取った参加者一覧
+#[derive(Debug, Default, Clone)]
+pub struct Roster {
+    members: Vec<Member>,
+    diagnostics: Vec<RowDiagnostic>,
+}
+
+impl Roster {
+    /// 最初に現れた順に並んだメンバー
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    /// 飛ばした行
+    pub fn diagnostics(&self) -> &[RowDiagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn into_members(self) -> Vec<Member> {
+        self.members
+    }
+}
+
+/// name,property_name,value 形式の参加者一覧を読み取ります
+///
+/// ヘッダーに必要な列がなければ strictness によらず失敗します。
+pub fn parse_roster(data: &str, strictness: Strictness) -> Result<Roster, RosterError> {
+    let mut reader = csv::Reader::from_reader(data.as_bytes());
+
+    // ヘッダーから列の位置を調べる
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(error) => {
+            return Err(RosterError {
+                diagnostics: vec![create_csv_diagnostic(&error, 1)],
+            })
+        }
+    };
+    let mut indicies = [0; COLUMNS.len()];
+    let mut diagnostics = Vec::default();
+    for (index, column) in COLUMNS.iter().enumerate() {
+        match headers.iter().position(|x| x.trim() == *column) {
+            Some(position) => indicies[index] = position,
+            None => diagnostics.push(RowDiagnostic {
+                line: 1,
+                field: Some(column.to_string()),
+                message: "missing column".to_string(),
+            }),
+        }
+    }
+    if !diagnostics.is_empty() {
+        return Err(RosterError { diagnostics });
+    }
+
+    let mut members: Vec<Member> = Vec::default();
+    let mut member_indicies: HashMap<String, usize> = HashMap::default();
+    let mut last_line = 1;
+    for result in reader.records() {
+        let record = match result {
+            Ok(record) => record,
+            Err(error) => {
+                diagnostics.push(create_csv_diagnostic(&error, last_line + 1));
+                continue;
+            }
+        };
+        let line = record.position().map(|x| x.line()).unwrap_or(last_line + 1);
+        last_line = line;
+
+        let [name, property_name, value] = indicies.map(|x| record.get(x).unwrap_or("").trim());
+        if name.is_empty() {
+            diagnostics.push(RowDiagnostic {
+                line,
+                field: Some("name".to_string()),
+                message: "empty name".to_string(),
+            });
+            continue;
+        }
+        if property_name.is_empty() {
+            diagnostics.push(RowDiagnostic {
+                line,
+                field: Some("property_name".to_string()),
+                message: "empty property name".to_string(),
+            });
+            continue;
+        }
+
+        // 最初に現れた順を保つ。値が読み取れなくてもメンバーは登録する
+        let index = *member_indicies.entry(name.to_string()).or_insert_with(|| {
+            members.push(Member::new(name, InstrumentType::empty()));
+            members.len() - 1
+        });
+
+        let instruments = if property_name == "instrument" {
+            match parse_instruments(value) {
+                Ok(parsed) => Some(parsed),
+                Err(error) => {
+                    diagnostics.push(RowDiagnostic {
+                        line,
+                        field: Some("value".to_string()),
+                        message: error.to_string(),
+                    });
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+
+        let member = &mut members[index];
+        match instruments {
+            Some(parsed) => {
+                member.instruments |= parsed.flags;
+                member.custom_instruments.extend(parsed.custom);
+            }
+            None => {
+                member
+                    .properties
+                    .insert(property_name.to_string(), value.to_string());
+            }
+        }
+    }
+
+    if strictness == Strictness::Strict && !diagnostics.is_empty() {
+        return Err(RosterError { diagnostics });
+    }
+    Ok(Roster {
+        members,
+        diagnostics,
+    })
+}
+
+fn create_csv_diagnostic(error: &csv::Error, line: u64) -> RowDiagnostic {
+    let line = error.position().map(|x| x.line()).unwrap_or(line);
+    RowDiagnostic {
+        line,
+        field: None,
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::InstrumentType;
+
+    use super::{parse_roster, Strictness};
+
+    const DATA: &str = "name,property_name,value\n\
+                        b,instrument,Drums\n\
+                        a,instrument,Vocal\n\
+                        c,instrument,Ukulele\n\
+                        b,grade,2\n\
+                        ,instrument,Vocal\n\
+                        a,instrument\n\
+                        a,instrument,ElectricGuitar\n\
+                        b,instrument,Other:ウクレレ\n";
+
+    #[test]
+    fn lenient() {
+        let roster = parse_roster(DATA, Strictness::Lenient).unwrap();
+
+        // 最初に現れた順。楽器を読み取れなかった c も含む
+        let names: Vec<&str> = roster.members().iter().map(|x| x.name()).collect();
+        assert_eq!(names, vec!["b", "a", "c"]);
+        assert!(roster.members()[2].instruments().is_empty());
+        assert_eq!(roster.members()[0].grade(), Some(2));
+        assert_eq!(roster.members()[0].custom_instruments(), ["ウクレレ"]);
+        assert_eq!(
+            roster.members()[1].instruments().bits(),
+            (InstrumentType::VOCAL | InstrumentType::ELECTRIC_GUITAR).bits()
+        );
+
+        let diagnostics: Vec<(u64, Option<&str>)> = roster
+            .diagnostics()
+            .iter()
+            .map(|x| (x.line, x.field.as_deref()))
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![(4, Some("value")), (6, Some("name")), (7, None)]
+        );
+        assert_eq!(
+            roster.diagnostics()[0].to_string(),
+            "line 4 (value): unknown instrument: Ukulele"
+        );
+    }
+
+    #[test]
+    fn strict() {
+        let error = parse_roster(DATA, Strictness::Strict).unwrap_err();
+        assert_eq!(error.diagnostics().len(), 3);
+
+        let data = "name,property_name,value\na,instrument,Vocal\n";
+        let roster = parse_roster(data, Strictness::Strict).unwrap();
+        assert_eq!(roster.members().len(), 1);
+        assert!(roster.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn missing_column() {
+        let error = parse_roster("name,value\na,Vocal\n", Strictness::Lenient).unwrap_err();
+        assert_eq!(error.diagnostics().len(), 1);
+        assert_eq!(error.diagnostics()[0].line, 1);
+        assert_eq!(
+            error.diagnostics()[0].field.as_deref(),
+            Some("property_name")
+        );
+    }
+}
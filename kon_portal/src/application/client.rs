@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+
+use kon_players::MemberList;
 use kon_rs::{Band, User};
 
 pub trait IClient {
@@ -6,4 +9,13 @@ pub trait IClient {
     fn fetch_user(&mut self, id: &str) -> Result<User, ()>;
 
     fn fetch_bands(&mut self) -> Vec<Band>;
+
+    // バンド名 -> 時間帯ごとに参加できるか
+    fn fetch_band_schedules(&mut self) -> HashMap<String, Vec<bool>>;
+
+    // 部屋ごとの枠数
+    fn fetch_rooms(&mut self) -> Vec<u8>;
+
+    // 担当できる楽器を含む参加者一覧
+    fn fetch_member_list(&mut self) -> MemberList;
 }
@@ -3,8 +3,9 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use kon_players::{check_band_lineups, LineupWarning, MemberList};
 use kon_rs::{
-    algorithm::{Schedule, Scheduler},
+    algorithm::{validate_live_info, RoomMatrix, Schedule, Scheduler, ValidationReport},
     Band, InstrumentType, User,
 };
 
@@ -20,6 +21,21 @@ where
     // 出演バンド
     bands: Option<Vec<Band>>,
 
+    // バンドごとの参加できる時間帯
+    band_schedules: Option<HashMap<String, Vec<bool>>>,
+
+    // 部屋ごとの枠数
+    rooms: Option<Vec<u8>>,
+
+    // 担当できる楽器を含む参加者一覧
+    member_list: Option<MemberList>,
+
+    // 入力の検証結果
+    validation_report: Option<ValidationReport>,
+
+    // バンドの編成の確認結果
+    lineup_warnings: Option<Vec<LineupWarning>>,
+
     // 練習スケジュール
     schedule: Option<Schedule>,
 
@@ -52,6 +68,11 @@ where
             user_ids: HashSet::default(),
             users: HashMap::default(),
             bands: None,
+            band_schedules: None,
+            rooms: None,
+            member_list: None,
+            validation_report: None,
+            lineup_warnings: None,
             schedule: None,
             client,
         }));
@@ -71,7 +92,13 @@ where
         let fetch_band_task_handle = tokio::spawn(async move {
             let mut shared_instance = task_instance.lock().unwrap();
             let bands = shared_instance.client.fetch_bands();
+            let band_schedules = shared_instance.client.fetch_band_schedules();
+            let rooms = shared_instance.client.fetch_rooms();
+            let member_list = shared_instance.client.fetch_member_list();
             shared_instance.bands = Some(bands);
+            shared_instance.band_schedules = Some(band_schedules);
+            shared_instance.rooms = Some(rooms);
+            shared_instance.member_list = Some(member_list);
         });
 
         Self {
@@ -106,7 +133,7 @@ where
             self.join_handles.remove(*index);
         }
 
-        let shared_instance = self.shared_instance.lock().unwrap();
+        let mut shared_instance = self.shared_instance.lock().unwrap();
         for user_id in &shared_instance.user_ids {
             if shared_instance.users.contains_key(user_id) {
                 continue;
@@ -125,6 +152,35 @@ where
             self.join_handles.push(handle);
         }
 
+        // スケジューリングの前に入力を検証
+        if shared_instance.validation_report.is_none() {
+            if let (Some(bands), Some(band_schedules), Some(rooms)) = (
+                &shared_instance.bands,
+                &shared_instance.band_schedules,
+                &shared_instance.rooms,
+            ) {
+                let band_table: HashMap<String, Vec<String>> = bands
+                    .iter()
+                    .map(|band| {
+                        let member_ids = band.member_ids().map(|id| id.to_string()).collect();
+                        (band.name.clone(), member_ids)
+                    })
+                    .collect();
+                let mut room_matrix_builder = RoomMatrix::builder();
+                for blocks in rooms {
+                    room_matrix_builder = room_matrix_builder.push_room(*blocks);
+                }
+                let report =
+                    validate_live_info(&band_table, band_schedules, &room_matrix_builder.build());
+                let lineup_warnings = shared_instance
+                    .member_list
+                    .as_ref()
+                    .map(|member_list| check_band_lineups(bands, member_list));
+                shared_instance.validation_report = Some(report);
+                shared_instance.lineup_warnings = lineup_warnings;
+            }
+        }
+
         // スケジュールの構築
         if let Some(_bands) = &shared_instance.bands {
             if shared_instance.schedule.is_none() {
@@ -151,14 +207,37 @@ where
         }
     }
 
-    pub fn for_each_band<TFunc: FnMut(&Band, &HashMap<String, User>)>(&self, mut func: TFunc) {
+    pub fn for_each_validation_message<TFunc: FnMut(bool, &str)>(&self, mut func: TFunc) {
+        let binding = self.shared_instance.lock().unwrap();
+
+        let Some(report) = &binding.validation_report else {
+            return;
+        };
+        for error in &report.errors {
+            func(true, &error.to_string());
+        }
+        for warning in &report.warnings {
+            func(false, &warning.to_string());
+        }
+    }
+
+    pub fn for_each_band<TFunc: FnMut(&Band, &HashMap<String, User>, &[&LineupWarning])>(
+        &self,
+        mut func: TFunc,
+    ) {
         let binding = self.shared_instance.lock().unwrap();
 
         let Some(bands) = &binding.bands else {
             return;
         };
         for band in bands {
-            func(band, &binding.users);
+            let lineup_warnings: Vec<&LineupWarning> = binding
+                .lineup_warnings
+                .iter()
+                .flatten()
+                .filter(|warning| warning.band_name() == band.name)
+                .collect();
+            func(band, &binding.users, &lineup_warnings);
         }
     }
 }
@@ -97,13 +97,10 @@ impl App {
     fn draw_members(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
         eframe::egui::CentralPanel::default().show(ctx, |ui| {
             // チェックボックスで担当楽器のフィルターを表示
-            let filter_list = vec![
-                ("Vocal", InstrumentType::VOCAL),
-                ("AcounsticGuitar", InstrumentType::ACOUSTIC_GUITAR),
-                ("ElectricGuitar", InstrumentType::ELECTRIC_GUITAR),
-                ("ElectricBass", InstrumentType::ELECTRIC_BASS),
-                ("Keyboard", InstrumentType::KEYBOARD),
-            ];
+            let filter_list: Vec<(String, InstrumentType)> = InstrumentType::all()
+                .iter()
+                .map(|instrument| (instrument.to_string(), instrument))
+                .collect();
 
             for (label, filter) in filter_list {
                 let mut is_enabled = self.instrument_filter.contains(filter);
@@ -164,15 +161,33 @@ impl App {
         });
 
         eframe::egui::SidePanel::right("Band List").show(ctx, |ui| {
-            self.workspace.for_each_band(|band, users| {
-                for member_id in &band.member_ids {
-                    let Some(user) = users.get(member_id) else {
-                        continue;
+            // 入力の問題はスケジュールより先に表示
+            self.workspace
+                .for_each_validation_message(|is_error, message| {
+                    let color = if is_error {
+                        eframe::egui::Color32::RED
+                    } else {
+                        eframe::egui::Color32::YELLOW
                     };
-                    ui.label(&user.name);
-                }
-                ui.separator();
-            });
+                    ui.colored_label(color, message);
+                });
+
+            self.workspace
+                .for_each_band(|band, users, lineup_warnings| {
+                    ui.strong(&band.name);
+                    for member_id in band.member_ids() {
+                        let Some(user) = users.get(member_id) else {
+                            continue;
+                        };
+                        ui.label(&user.name);
+                    }
+
+                    // 編成の過不足
+                    for warning in lineup_warnings {
+                        ui.colored_label(eframe::egui::Color32::YELLOW, warning.to_string());
+                    }
+                    ui.separator();
+                });
         });
     }
 }
@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 
-use kon_rs::{Band, InstrumentType, User};
+use kon_players::{
+    clients::{IClient as _, SampleClient},
+    MemberList,
+};
+use kon_rs::{Band, BandMember, InstrumentType, User};
 
 use crate::application::IClient;
 
@@ -8,6 +12,8 @@ pub struct MockClient {
     user_ids: Vec<String>,
     users: HashMap<String, User>,
     bands: Vec<Band>,
+    band_schedules: HashMap<String, Vec<bool>>,
+    rooms: Vec<u8>,
 }
 
 impl MockClient {
@@ -43,12 +49,25 @@ impl MockClient {
             ]),
             bands: vec![
                 Band {
-                    member_ids: vec!["shikama_shuto".to_string(), "edogawa_conan".to_string()],
+                    name: "Cool Band".to_string(),
+                    members: vec![
+                        BandMember::new("shikama_shuto", InstrumentType::ELECTRIC_BASS),
+                        BandMember::new("edogawa_conan", InstrumentType::VOCAL),
+                    ],
                 },
                 Band {
-                    member_ids: vec!["shikama_shuto".to_string(), "hattori_heiji".to_string()],
+                    name: "Pank Band".to_string(),
+                    members: vec![
+                        BandMember::new("shikama_shuto", InstrumentType::ELECTRIC_GUITAR),
+                        BandMember::new("hattori_heiji", InstrumentType::TROMBONE),
+                    ],
                 },
             ],
+            band_schedules: HashMap::from([
+                ("Cool Band".to_string(), vec![true, true]),
+                ("Pank Band".to_string(), vec![true, false]),
+            ]),
+            rooms: vec![1, 1],
         }
     }
 }
@@ -69,4 +88,17 @@ impl IClient for MockClient {
     fn fetch_bands(&mut self) -> Vec<Band> {
         self.bands.to_vec()
     }
+
+    fn fetch_band_schedules(&mut self) -> HashMap<String, Vec<bool>> {
+        self.band_schedules.clone()
+    }
+
+    fn fetch_rooms(&mut self) -> Vec<u8> {
+        self.rooms.clone()
+    }
+
+    fn fetch_member_list(&mut self) -> MemberList {
+        let data = SampleClient.fetch().unwrap_or_default();
+        MemberList::from_csv(&data)
+    }
 }
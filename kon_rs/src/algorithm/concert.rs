@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+
+use crate::{Band, InstrumentType, LiveProgram};
+
+use super::running_order::{count_setup_changes, SETUP_INSTRUMENTS};
+use super::{LocalDateTime, SpanTime};
+
+/// ライブ当日の時間設定
+///
+/// 出演時間や転換時間を分単位で指定します。バンドは LiveProgram の出演バンド名で指定します。
+pub struct ConcertSetting {
+    // 開場時刻
+    doors: LocalDateTime,
+
+    // 開場から開演までの時間
+    start_delay_minutes: u32,
+
+    // 会場を出なければいけない時刻
+    hard_end: Option<LocalDateTime>,
+
+    default_set_minutes: u32,
+    set_minutes_table: HashMap<String, u32>,
+
+    // 転換の最低限の時間と、機材の搬入・搬出ひとつあたりに追加する時間
+    changeover_minutes: u32,
+    setup_change_minutes: u32,
+
+    setup_instruments: InstrumentType,
+    band_instrument_table: HashMap<String, InstrumentType>,
+
+    // 何番目のバンドの後に何分休憩するか
+    breaks: HashMap<usize, u32>,
+}
+
+impl ConcertSetting {
+    pub fn new(doors: LocalDateTime) -> Self {
+        Self {
+            doors,
+            start_delay_minutes: 30,
+            hard_end: None,
+            default_set_minutes: 20,
+            set_minutes_table: HashMap::default(),
+            changeover_minutes: 10,
+            setup_change_minutes: 5,
+            setup_instruments: SETUP_INSTRUMENTS,
+            band_instrument_table: HashMap::default(),
+            breaks: HashMap::default(),
+        }
+    }
+
+    /// 開場から開演までの時間
+    pub fn with_start_delay(mut self, minutes: u32) -> Self {
+        self.start_delay_minutes = minutes;
+        self
+    }
+
+    /// 会場を出なければいけない時刻。これを過ぎる出演を超過として扱います
+    pub fn with_hard_end(mut self, hard_end: LocalDateTime) -> Self {
+        self.hard_end = Some(hard_end);
+        self
+    }
+
+    /// 出演時間を指定していないバンドの出演時間
+    pub fn with_default_set_minutes(mut self, minutes: u32) -> Self {
+        self.default_set_minutes = minutes;
+        self
+    }
+
+    /// バンドの出演時間
+    pub fn with_set_minutes(mut self, band_name: &str, minutes: u32) -> Self {
+        self.set_minutes_table
+            .insert(band_name.to_string(), minutes);
+        self
+    }
+
+    /// 転換時間
+    ///
+    /// 前後のバンドで搬入・搬出が必要な楽器ひとつごとに setup_change_minutes を追加します。
+    pub fn with_changeover_minutes(mut self, minutes: u32, setup_change_minutes: u32) -> Self {
+        self.changeover_minutes = minutes;
+        self.setup_change_minutes = setup_change_minutes;
+        self
+    }
+
+    /// 入れ替えに手間がかかる楽器を指定します
+    pub fn with_setup_instruments(mut self, setup_instruments: InstrumentType) -> Self {
+        self.setup_instruments = setup_instruments;
+        self
+    }
+
+    /// バンドが使う楽器を指定します
+    pub fn with_band_instruments(mut self, band_name: &str, instruments: InstrumentType) -> Self {
+        self.band_instrument_table
+            .insert(band_name.to_string(), instruments);
+        self
+    }
+
+    /// メンバーの担当楽器からバンドが使う楽器を指定します
+    pub fn with_band_lineups(mut self, bands: &[Band]) -> Self {
+        for band in bands {
+            self.band_instrument_table
+                .insert(band.name.clone(), band.instruments());
+        }
+        self
+    }
+
+    /// index 番目 (0 始まり) のバンドの後に休憩を入れます
+    ///
+    /// 転換は休憩中に済ませるので、休憩と転換の長い方だけ間を空けます。
+    pub fn push_break(mut self, index: usize, minutes: u32) -> Self {
+        self.breaks.insert(index, minutes);
+        self
+    }
+
+    /// バンドの出演時間
+    pub fn set_minutes(&self, band_name: &str) -> u32 {
+        *self
+            .set_minutes_table
+            .get(band_name)
+            .unwrap_or(&self.default_set_minutes)
+    }
+
+    /// 前後のバンドの間の転換時間
+    pub fn changeover_minutes(&self, before: &str, after: &str) -> u32 {
+        let instruments = |band_name: &str| {
+            self.band_instrument_table
+                .get(band_name)
+                .copied()
+                .unwrap_or_default()
+        };
+        let setup_change_count = count_setup_changes(
+            instruments(before),
+            instruments(after),
+            self.setup_instruments,
+        );
+        self.changeover_minutes + setup_change_count * self.setup_change_minutes
+    }
+}
+
+/// 出演ひとつ分の時間
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcertSlot {
+    pub band_name: String,
+
+    /// 出演の開始・終了時刻
+    pub time: SpanTime,
+
+    /// 直前のバンドとの間の時間 (転換・休憩)。最初のバンドは 0
+    pub interval_minutes: u32,
+}
+
+/// ライブ当日のタイムテーブル
+pub struct ConcertTimetable {
+    doors: LocalDateTime,
+    slots: Vec<ConcertSlot>,
+    hard_end: Option<LocalDateTime>,
+}
+
+impl ConcertTimetable {
+    pub(crate) fn new(program: &LiveProgram, setting: &ConcertSetting) -> Self {
+        let mut slots: Vec<ConcertSlot> = Vec::default();
+        let mut current = setting
+            .doors
+            .add_minutes(setting.start_delay_minutes as i64);
+        for (index, band_name) in program.item.iter().enumerate() {
+            let interval_minutes = match slots.last() {
+                Some(before) => {
+                    let changeover_minutes =
+                        setting.changeover_minutes(&before.band_name, band_name);
+                    let break_minutes = setting.breaks.get(&(index - 1)).copied().unwrap_or(0);
+                    changeover_minutes.max(break_minutes)
+                }
+                None => 0,
+            };
+
+            let start = current.add_minutes(interval_minutes as i64);
+            let end = start.add_minutes(setting.set_minutes(band_name) as i64);
+            slots.push(ConcertSlot {
+                band_name: band_name.to_string(),
+                time: SpanTime::new(start, end),
+                interval_minutes,
+            });
+            current = end;
+        }
+
+        Self {
+            doors: setting.doors,
+            slots,
+            hard_end: setting.hard_end,
+        }
+    }
+
+    pub fn doors(&self) -> LocalDateTime {
+        self.doors
+    }
+
+    /// 出演順に並んだ出演時間
+    pub fn slots(&self) -> &[ConcertSlot] {
+        &self.slots
+    }
+
+    /// 最後のバンドの終演時刻
+    pub fn end(&self) -> Option<LocalDateTime> {
+        self.slots.last().map(|slot| slot.time.end)
+    }
+
+    /// 会場を出る時刻を過ぎる出演
+    pub fn overruns(&self) -> Vec<&ConcertSlot> {
+        let Some(hard_end) = self.hard_end else {
+            return Vec::default();
+        };
+
+        self.slots
+            .iter()
+            .filter(|slot| hard_end < slot.time.end)
+            .collect()
+    }
+
+    /// 終演が会場を出る時刻を何分過ぎるか。間に合うなら 0
+    pub fn overrun_minutes(&self) -> u32 {
+        let (Some(hard_end), Some(end)) = (self.hard_end, self.end()) else {
+            return 0;
+        };
+
+        end.minutes_since(&hard_end).max(0) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithm::tests::create_program;
+    use crate::algorithm::LocalDateTime;
+    use crate::{Band, BandMember, InstrumentType};
+
+    use super::ConcertSetting;
+
+    #[test]
+    fn timetable() {
+        let setting = ConcertSetting::new(LocalDateTime::new(2024, 4, 6, 17, 30))
+            .with_set_minutes("band_a", 25)
+            .with_changeover_minutes(10, 5)
+            .with_band_instruments("band_b", InstrumentType::DRUMS | InstrumentType::KEYBOARD)
+            .push_break(1, 15);
+        let timetable = create_program().create_timetable(&setting);
+
+        let times: Vec<String> = timetable
+            .slots()
+            .iter()
+            .map(|slot| slot.time.to_time_string())
+            .collect();
+        // band_a 18:00 開演
+        // band_b はドラムとキーボードの搬入で 10 + 5 * 2 分の転換
+        // band_c は休憩 15 分と転換 20 分の長い方
+        assert_eq!(times, vec!["18:00-18:25", "18:45-19:05", "19:25-19:45"]);
+        assert_eq!(timetable.slots()[1].interval_minutes, 20);
+        assert_eq!(
+            timetable.end(),
+            Some(LocalDateTime::new(2024, 4, 6, 19, 45))
+        );
+
+        // 会場の時間に間に合う
+        assert!(timetable.overruns().is_empty());
+        assert_eq!(timetable.overrun_minutes(), 0);
+    }
+
+    // メンバーの担当楽器から転換時間を求める
+    #[test]
+    fn lineup() {
+        let bands = [
+            Band {
+                name: "band_a".to_string(),
+                members: vec![
+                    BandMember::new("a", InstrumentType::ELECTRIC_BASS),
+                    BandMember::new("b", InstrumentType::DRUMS),
+                ],
+            },
+            Band {
+                name: "band_b".to_string(),
+                members: vec![BandMember::new("a", InstrumentType::ELECTRIC_GUITAR)],
+            },
+        ];
+        let setting = ConcertSetting::new(LocalDateTime::new(2024, 4, 6, 17, 30))
+            .with_changeover_minutes(10, 5)
+            .with_band_lineups(&bands);
+        assert_eq!(setting.changeover_minutes("band_a", "band_b"), 15);
+        assert_eq!(setting.changeover_minutes("band_b", "band_c"), 10);
+    }
+
+    #[test]
+    fn overrun() {
+        let setting = ConcertSetting::new(LocalDateTime::new(2024, 4, 6, 17, 30))
+            .with_start_delay(0)
+            .with_default_set_minutes(30)
+            .with_changeover_minutes(0, 0)
+            .with_hard_end(LocalDateTime::new(2024, 4, 6, 18, 45));
+        let timetable = create_program().create_timetable(&setting);
+
+        let overruns = timetable.overruns();
+        assert_eq!(overruns.len(), 1);
+        assert_eq!(overruns[0].band_name, "band_c");
+        assert_eq!(timetable.overrun_minutes(), 15);
+    }
+}
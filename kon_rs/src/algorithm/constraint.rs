@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+
+use crate::BandId;
+
+use super::detail::{
+    BandScheduleTraverseDecorator, ITraverseDecorator, MemberConflictTraverseDecorator,
+    TreeTraverser,
+};
+use super::{LiveInfo, RoomMatrix, TraverseOperation};
+
+/// スケジュールの走査中に適用する制約
+///
+/// indicies[i] は room_matrix.blocks()[i] の枠に割り当てる live_info.band_ids() のインデックスです。
+/// LocalSearchScheduler で一部の枠が空いているときは、空き枠を負の値で表します。
+/// 制約を満たさない場合は TraverseOperation::Skip や TraverseOperation::Pruning を返して枝刈りします。
+pub trait IConstraint {
+    fn invoke(
+        &self,
+        indicies: &[i32],
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> TraverseOperation;
+}
+
+/// バンドのスケジュールが合わない枠には割り当てない
+#[derive(Default, Clone)]
+pub struct BandScheduleConstraint;
+
+impl IConstraint for BandScheduleConstraint {
+    fn invoke(
+        &self,
+        indicies: &[i32],
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> TraverseOperation {
+        BandScheduleTraverseDecorator::new(TreeTraverser).invoke_with_room_matrix(
+            indicies,
+            room_matrix,
+            live_info,
+        )
+    }
+}
+
+/// 同じ時間帯にメンバーが重複するバンドを割り当てない
+#[derive(Default, Clone)]
+pub struct MemberConflictConstraint;
+
+impl IConstraint for MemberConflictConstraint {
+    fn invoke(
+        &self,
+        indicies: &[i32],
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> TraverseOperation {
+        MemberConflictTraverseDecorator::new(TreeTraverser).invoke_with_room_matrix(
+            indicies,
+            room_matrix,
+            live_info,
+        )
+    }
+}
+
+/// ふたつのバンドの間の関係
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairRelation {
+    /// 前者が後者より前の時間帯に入る
+    Before,
+
+    /// 前者が後者より後の時間帯に入る
+    After,
+
+    /// 同じ時間帯に入る
+    SameSpan,
+
+    /// 異なる時間帯に入る
+    DifferentSpan,
+
+    /// 同じ部屋に入る
+    SameRoom,
+
+    /// 同じ時間帯に隣り合う部屋に入らない
+    NotAdjacentRoom,
+}
+
+/// バンド同士の関係を指定する制約
+///
+/// 機材の貸し借りや掛け持ちメンバーの都合など、バンドの組ごとの要望を走査中に枝刈りします。
+pub struct PairConstraint {
+    // 枠 (room_matrix.blocks() の順) -> (時間帯のインデックス, 部屋のインデックス)
+    block_locations: Vec<(usize, usize)>,
+
+    // バンドの組と関係
+    // assign_partial では一部のバンドだけの LiveInfo で走査するので、インデックスではなく識別子で持つ
+    rules: Vec<(BandId, BandId, PairRelation)>,
+
+    band_ids: Vec<BandId>,
+}
+
+impl PairConstraint {
+    pub fn new(room_matrix: &RoomMatrix, live_info: &LiveInfo) -> Self {
+        let block_locations = room_matrix
+            .blocks()
+            .iter()
+            .map(|block_id| room_matrix.block_location(*block_id).unwrap())
+            .collect();
+
+        Self {
+            block_locations,
+            rules: Vec::default(),
+            band_ids: live_info.band_ids().to_vec(),
+        }
+    }
+
+    /// バンドの組に関係を追加します。存在しないバンドは無視します
+    pub fn push(mut self, lhs: BandId, rhs: BandId, relation: PairRelation) -> Self {
+        if self.band_ids.contains(&lhs) && self.band_ids.contains(&rhs) {
+            self.rules.push((lhs, rhs, relation));
+        }
+        self
+    }
+
+    fn is_satisfied(
+        &self,
+        lhs: (usize, usize),
+        rhs: (usize, usize),
+        relation: PairRelation,
+    ) -> bool {
+        let (lhs_span, lhs_room) = lhs;
+        let (rhs_span, rhs_room) = rhs;
+        match relation {
+            PairRelation::Before => lhs_span < rhs_span,
+            PairRelation::After => lhs_span > rhs_span,
+            PairRelation::SameSpan => lhs_span == rhs_span,
+            PairRelation::DifferentSpan => lhs_span != rhs_span,
+            PairRelation::SameRoom => lhs_room == rhs_room,
+            PairRelation::NotAdjacentRoom => {
+                lhs_span != rhs_span || lhs_room.abs_diff(rhs_room) != 1
+            }
+        }
+    }
+}
+
+impl IConstraint for PairConstraint {
+    fn invoke(
+        &self,
+        indicies: &[i32],
+        _room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> TraverseOperation {
+        // バンド -> 割り当てた枠の位置
+        let positions: HashMap<BandId, usize> = indicies
+            .iter()
+            .take(self.block_locations.len())
+            .enumerate()
+            .filter_map(|(position, band_index)| {
+                let band_id = live_info
+                    .band_ids()
+                    .get(usize::try_from(*band_index).ok()?)?;
+                Some((*band_id, position))
+            })
+            .collect();
+
+        // 違反した組のうち、なるべく手前の位置で枝刈りする
+        let mut skip_index = None;
+        for (lhs, rhs, relation) in &self.rules {
+            let (Some(lhs_position), Some(rhs_position)) = (positions.get(lhs), positions.get(rhs))
+            else {
+                continue;
+            };
+            let (lhs_position, rhs_position) = (*lhs_position, *rhs_position);
+
+            let lhs_location = self.block_locations[lhs_position];
+            let rhs_location = self.block_locations[rhs_position];
+            if self.is_satisfied(lhs_location, rhs_location, *relation) {
+                continue;
+            }
+
+            let index = lhs_position.max(rhs_position) + 1;
+            skip_index = Some(skip_index.map_or(index, |current: usize| current.min(index)));
+        }
+
+        match skip_index {
+            Some(index) => TraverseOperation::Skip(index),
+            None => TraverseOperation::Next,
+        }
+    }
+}
+
+/// メンバーが出番の間に待つ時間帯の数に上限を設ける制約
+///
+/// 朝一番と最後の枠の両方に入って何時間も待つようなスケジュールを枝刈りします。
+pub struct MemberIdleConstraint {
+    // 出番の間に待てる時間帯の数
+    max_idle_spans: usize,
+
+    // 枠 (room_matrix.blocks() の順) -> 時間帯のインデックス
+    block_spans: Vec<usize>,
+
+    // 時間帯 -> その時間帯の枠のうち最も後ろの位置
+    span_last_positions: Vec<usize>,
+
+    // バンド -> live_info.user_ids() のインデックス
+    band_members: HashMap<BandId, Vec<usize>>,
+
+    user_count: usize,
+}
+
+impl MemberIdleConstraint {
+    pub fn new(room_matrix: &RoomMatrix, live_info: &LiveInfo, max_idle_spans: usize) -> Self {
+        let block_spans: Vec<usize> = room_matrix
+            .blocks()
+            .iter()
+            .map(|block_id| room_matrix.block_location(*block_id).unwrap().0)
+            .collect();
+
+        let mut span_last_positions = vec![0; room_matrix.spans().len()];
+        for (position, span_index) in block_spans.iter().enumerate() {
+            span_last_positions[*span_index] = span_last_positions[*span_index].max(position);
+        }
+
+        let user_ids = live_info.user_ids();
+        let band_members = live_info
+            .band_ids()
+            .iter()
+            .map(|band_id| {
+                let member_indicies = live_info
+                    .band_member_ids(*band_id)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|member_id| user_ids.iter().position(|id| id == member_id))
+                    .collect();
+                (*band_id, member_indicies)
+            })
+            .collect();
+
+        Self {
+            max_idle_spans,
+            block_spans,
+            span_last_positions,
+            band_members,
+            user_count: user_ids.len(),
+        }
+    }
+}
+
+impl IConstraint for MemberIdleConstraint {
+    fn invoke(
+        &self,
+        indicies: &[i32],
+        _room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> TraverseOperation {
+        // メンバー -> 出番の時間帯
+        let mut member_spans = vec![Vec::default(); self.user_count];
+        for (position, band_index) in indicies.iter().take(self.block_spans.len()).enumerate() {
+            // 空き枠
+            let Ok(band_index) = usize::try_from(*band_index) else {
+                continue;
+            };
+            let Some(member_indicies) = live_info
+                .band_ids()
+                .get(band_index)
+                .and_then(|band_id| self.band_members.get(band_id))
+            else {
+                continue;
+            };
+            for member_index in member_indicies {
+                member_spans[*member_index].push(self.block_spans[position]);
+            }
+        }
+
+        // 待ち時間は間の時間帯の枠がすべて決まった時点で確定するので、そこまでを枝刈りする
+        let mut skip_index = None;
+        for spans in &mut member_spans {
+            spans.sort();
+            for span in spans.windows(2) {
+                if span[1] - span[0] <= self.max_idle_spans + 1 {
+                    continue;
+                }
+
+                let index = self.span_last_positions[span[0]..=span[1]]
+                    .iter()
+                    .max()
+                    .unwrap()
+                    + 1;
+                skip_index = Some(skip_index.map_or(index, |current: usize| current.min(index)));
+            }
+        }
+
+        match skip_index {
+            Some(index) => TraverseOperation::Skip(index),
+            None => TraverseOperation::Next,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::algorithm::{create_live_info, RoomMatrix, Scheduler};
+
+    use super::{
+        BandScheduleConstraint, MemberConflictConstraint, MemberIdleConstraint, PairConstraint,
+        PairRelation,
+    };
+
+    fn assign(relation: PairRelation) -> usize {
+        // 2 部屋 2 枠ずつ
+        let room_matrix = RoomMatrix::builder().push_room(2).push_room(2).build();
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+            ("band_c".to_string(), vec!["c".to_string()]),
+            ("band_d".to_string(), vec!["d".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 2]))
+            .collect();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let band_a = live_info.band_ids()[0];
+        let band_b = live_info.band_ids()[1];
+        let pair_constraint =
+            PairConstraint::new(&room_matrix, &live_info).push(band_a, band_b, relation);
+        let scheduler = Scheduler::builder()
+            .push_constraint(BandScheduleConstraint)
+            .push_constraint(MemberConflictConstraint)
+            .push_constraint(pair_constraint)
+            .build();
+        scheduler.assign(&room_matrix, &live_info).unwrap().len()
+    }
+
+    // 4! = 24 通りのうち条件を満たすもの
+    #[test]
+    fn pair_relation() {
+        assert_eq!(assign(PairRelation::Before), 8);
+        assert_eq!(assign(PairRelation::After), 8);
+        assert_eq!(assign(PairRelation::SameSpan), 8);
+        assert_eq!(assign(PairRelation::DifferentSpan), 16);
+        assert_eq!(assign(PairRelation::SameRoom), 8);
+        assert_eq!(assign(PairRelation::NotAdjacentRoom), 16);
+    }
+
+    #[test]
+    fn member_idle() {
+        // 1 部屋 4 枠で a のバンドがふたつ
+        let room_matrix = RoomMatrix::builder().push_room(4).build();
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["a".to_string()]),
+            ("band_c".to_string(), vec!["c".to_string()]),
+            ("band_d".to_string(), vec!["d".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 4]))
+            .collect();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let assign = |max_idle_spans| {
+            let scheduler = Scheduler::builder()
+                .push_constraint(BandScheduleConstraint)
+                .push_constraint(MemberConflictConstraint)
+                .push_constraint(MemberIdleConstraint::new(
+                    &room_matrix,
+                    &live_info,
+                    max_idle_spans,
+                ))
+                .build();
+            scheduler.assign(&room_matrix, &live_info).unwrap()
+        };
+
+        // 4! = 24 通りのうち a の出番の間隔が
+        // 1 (待ちなし) が 12 通り、2 が 8 通り、3 が 4 通り
+        assert_eq!(assign(0).len(), 12);
+        assert_eq!(assign(1).len(), 20);
+        assert_eq!(assign(2).len(), 24);
+    }
+}
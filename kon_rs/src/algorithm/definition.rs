@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use crate::{BandId, BlockId, RoomId, SpanId};
+use crate::{BlockId, RoomId, SpanId};
+
+use super::{SchedulerError, SpanTime};
 
 pub enum TraverseOperation {
     // 順番に走査
@@ -16,6 +18,8 @@ pub enum TraverseOperation {
 #[derive(Default)]
 pub struct RoomMatrixBuilder {
     blocks: Vec<u8>,
+    room_names: Vec<Option<String>>,
+    span_times: Vec<SpanTime>,
 }
 
 impl RoomMatrixBuilder {
@@ -62,17 +66,41 @@ impl RoomMatrixBuilder {
             }
         }
 
+        // 部屋名と時間帯の時刻
+        let room_name_table = rooms
+            .iter()
+            .zip(self.room_names)
+            .filter_map(|(id, name)| Some((*id, name?)))
+            .collect();
+        let span_time_table = spans.iter().copied().zip(self.span_times).collect();
+
         RoomMatrix {
             rooms,
             spans,
             blocks,
             room_block_table,
             span_block_table,
+            room_name_table,
+            span_time_table,
         }
     }
 
     pub fn push_room(mut self, block_count: u8) -> Self {
         self.blocks.push(block_count);
+        self.room_names.push(None);
+        self
+    }
+
+    /// 名前つきの部屋を追加します
+    pub fn push_named_room(mut self, name: &str, block_count: u8) -> Self {
+        self.blocks.push(block_count);
+        self.room_names.push(Some(name.to_string()));
+        self
+    }
+
+    /// 時間帯の時刻を先頭の時間帯から順に追加します
+    pub fn push_span_time(mut self, span_time: SpanTime) -> Self {
+        self.span_times.push(span_time);
         self
     }
 }
@@ -93,6 +121,12 @@ pub struct RoomMatrix {
 
     // 時間帯で利用可能な枠
     span_block_table: HashMap<SpanId, Vec<BlockId>>,
+
+    // 部屋名
+    room_name_table: HashMap<RoomId, String>,
+
+    // 時間帯の開始・終了時刻
+    span_time_table: HashMap<SpanId, SpanTime>,
 }
 
 impl RoomMatrix {
@@ -124,15 +158,61 @@ impl RoomMatrix {
     pub fn iter_span_blocks(&self, span_id: SpanId) -> impl Iterator<Item = &BlockId> {
         self.span_block_table.get(&span_id).unwrap().iter()
     }
-}
 
-pub struct Schedule {
-    pub assign_table: HashMap<BandId, RoomId>,
+    /// 指定した部屋で利用可能な枠。存在しない部屋なら SchedulerError::UnknownRoom
+    pub fn try_iter_room_blocks(
+        &self,
+        room_id: RoomId,
+    ) -> Result<impl Iterator<Item = &BlockId>, SchedulerError> {
+        self.room_block_table
+            .get(&room_id)
+            .map(|blocks| blocks.iter())
+            .ok_or(SchedulerError::UnknownRoom)
+    }
+
+    /// 指定した時間帯で利用可能な枠。存在しない時間帯なら SchedulerError::UnknownSpan
+    pub fn try_iter_span_blocks(
+        &self,
+        span_id: SpanId,
+    ) -> Result<impl Iterator<Item = &BlockId>, SchedulerError> {
+        self.span_block_table
+            .get(&span_id)
+            .map(|blocks| blocks.iter())
+            .ok_or(SchedulerError::UnknownSpan)
+    }
+
+    /// 部屋名。名前をつけていなければ None
+    pub fn room_name(&self, room_id: RoomId) -> Option<&str> {
+        self.room_name_table.get(&room_id).map(|name| name.as_str())
+    }
+
+    /// 時間帯の時刻。時刻を指定していなければ None
+    pub fn span_time(&self, span_id: SpanId) -> Option<SpanTime> {
+        self.span_time_table.get(&span_id).copied()
+    }
+
+    /// 枠が何番目の時間帯の何番目の部屋にあるか
+    pub fn block_location(&self, block_id: BlockId) -> Option<(usize, usize)> {
+        self.rooms
+            .iter()
+            .enumerate()
+            .find_map(|(room_index, room_id)| {
+                let span_index = self
+                    .room_block_table
+                    .get(room_id)?
+                    .iter()
+                    .position(|id| *id == block_id)?;
+                Some((span_index, room_index))
+            })
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
+    use crate::algorithm::{LocalDateTime, SchedulerError, SpanTime};
+    use crate::{BlockId, RoomId, SpanId};
+
     use super::RoomMatrix;
 
     #[test]
@@ -147,6 +227,20 @@ mod tests {
         let room_matrix = RoomMatrix::builder().push_room(2).build();
         let span_id = room_matrix.spans()[1];
         assert_eq!(room_matrix.iter_span_blocks(span_id).count(), 1);
+
+        // 存在しない部屋と時間帯
+        assert_eq!(
+            room_matrix.try_iter_span_blocks(span_id).unwrap().count(),
+            1
+        );
+        assert!(matches!(
+            room_matrix.try_iter_span_blocks(SpanId::new()),
+            Err(SchedulerError::UnknownSpan)
+        ));
+        assert!(matches!(
+            room_matrix.try_iter_room_blocks(RoomId::new()),
+            Err(SchedulerError::UnknownRoom)
+        ));
     }
 
     #[test]
@@ -164,4 +258,38 @@ mod tests {
         assert_eq!(room_matrix.iter_span_blocks(span_id_1).count(), 2);
         assert_eq!(room_matrix.iter_span_blocks(span_id_2).count(), 1);
     }
+
+    #[test]
+    fn room_matrix_block_location() {
+        let room_matrix = RoomMatrix::builder().push_room(1).push_room(2).build();
+
+        let room_id = room_matrix.rooms()[1];
+        let block_id = *room_matrix.iter_room_blocks(room_id).nth(1).unwrap();
+        assert_eq!(room_matrix.block_location(block_id), Some((1, 1)));
+        assert_eq!(room_matrix.block_location(BlockId::new()), None);
+    }
+
+    #[test]
+    fn room_matrix_name_and_time() {
+        let span_time = SpanTime::new(
+            LocalDateTime::new(2024, 4, 1, 10, 0),
+            LocalDateTime::new(2024, 4, 1, 11, 0),
+        );
+        let room_matrix = RoomMatrix::builder()
+            .push_named_room("Studio A", 2)
+            .push_room(1)
+            .push_span_time(span_time)
+            .build();
+
+        assert_eq!(
+            room_matrix.room_name(room_matrix.rooms()[0]),
+            Some("Studio A")
+        );
+        assert_eq!(room_matrix.room_name(room_matrix.rooms()[1]), None);
+        assert_eq!(
+            room_matrix.span_time(room_matrix.spans()[0]),
+            Some(span_time)
+        );
+        assert_eq!(room_matrix.span_time(room_matrix.spans()[1]), None);
+    }
 }
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use crate::algorithm::{IConstraint, LiveInfo, RoomMatrix, TraverseOperation};
+
+use super::pruning_decorators::ITraverseDecorator;
+
+// 制約を登録順に適用する
+// 最初に枝刈りを要求した制約の結果を採用する
+#[derive(Default, Clone)]
+pub struct ConstraintChain {
+    constraints: Vec<Arc<dyn IConstraint + Send + Sync>>,
+}
+
+impl ConstraintChain {
+    pub fn push(&mut self, constraint: Arc<dyn IConstraint + Send + Sync>) {
+        self.constraints.push(constraint);
+    }
+}
+
+impl ITraverseDecorator for ConstraintChain {
+    fn invoke_with_room_matrix(
+        &self,
+        indicies: &[i32],
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> TraverseOperation {
+        for constraint in &self.constraints {
+            match constraint.invoke(indicies, room_matrix, live_info) {
+                TraverseOperation::Next => continue,
+                operation => return operation,
+            }
+        }
+
+        TraverseOperation::Next
+    }
+}
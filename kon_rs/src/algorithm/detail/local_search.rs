@@ -0,0 +1,357 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::algorithm::{
+    IScheduleCallback, LiveInfo, MultiObjective, RoomMatrix, Schedule, SchedulerError,
+    SchedulerInfo, TraverseOperation,
+};
+use crate::{BandId, BlockId};
+
+use super::util::Random;
+use super::{ConstraintChain, ITraverseDecorator};
+
+// 焼きなまし法の温度
+const TEMPERATURE_START: f64 = 1.0;
+const TEMPERATURE_END: f64 = 0.01;
+
+pub struct LocalSearchParams {
+    // 探索を打ち切る時間
+    pub time_limit: Duration,
+
+    // 探索の反復回数
+    pub iteration_count: usize,
+
+    // 乱数のシード値
+    pub seed: u64,
+
+    // 一部のバンドだけを割り当てた結果も許すか
+    pub allow_partial: bool,
+}
+
+// 枠とバンドの対応
+#[derive(Clone)]
+struct AssignState {
+    // 枠ごとに割り当てたバンドのインデックス
+    slots: Vec<Option<usize>>,
+
+    // 割り当てられなかったバンドのインデックス
+    bench: Vec<usize>,
+}
+
+pub struct LocalSearchImpl<'a> {
+    room_matrix: &'a RoomMatrix,
+    live_info: &'a LiveInfo,
+
+    // 登録された制約
+    constraints: &'a ConstraintChain,
+
+    // 部屋順に並べた枠
+    blocks: Vec<BlockId>,
+
+    // room_matrix.blocks() の順 -> 枠のインデックス
+    block_positions: Vec<usize>,
+
+    // 枠 -> 時間帯のインデックス
+    block_span_indicies: Vec<usize>,
+
+    // 時間帯 -> 枠のインデックス
+    span_block_indicies: Vec<Vec<usize>>,
+
+    // 通知するスケジュールで共有する部屋割りとバンドの情報
+    layout: Schedule,
+}
+
+impl<'a> LocalSearchImpl<'a> {
+    pub fn new(
+        room_matrix: &'a RoomMatrix,
+        live_info: &'a LiveInfo,
+        constraints: &'a ConstraintChain,
+    ) -> Self {
+        let blocks: Vec<BlockId> = room_matrix
+            .rooms()
+            .iter()
+            .flat_map(|room_id| room_matrix.iter_room_blocks(*room_id).copied())
+            .collect();
+
+        let block_index_table: HashMap<BlockId, usize> = blocks
+            .iter()
+            .enumerate()
+            .map(|(index, block_id)| (*block_id, index))
+            .collect();
+
+        let block_positions = room_matrix
+            .blocks()
+            .iter()
+            .map(|block_id| block_index_table[block_id])
+            .collect();
+
+        let mut block_span_indicies = vec![0; blocks.len()];
+        let span_block_indicies: Vec<Vec<usize>> = room_matrix
+            .spans()
+            .iter()
+            .enumerate()
+            .map(|(span_index, span_id)| {
+                room_matrix
+                    .iter_span_blocks(*span_id)
+                    .map(|block_id| {
+                        let block_index = block_index_table[block_id];
+                        block_span_indicies[block_index] = span_index;
+                        block_index
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            room_matrix,
+            live_info,
+            constraints,
+            blocks,
+            block_positions,
+            block_span_indicies,
+            span_block_indicies,
+            layout: Schedule::new(HashMap::default(), room_matrix, live_info),
+        }
+    }
+
+    pub fn run<T: IScheduleCallback>(
+        &self,
+        objective: &MultiObjective,
+        params: &LocalSearchParams,
+        callback: &mut T,
+    ) -> Result<HashMap<BlockId, BandId>, SchedulerError> {
+        // 一部のバンドだけの割り当てを許さないなら、そもそも部屋数が足りてなければ失敗
+        if !params.allow_partial && self.blocks.len() < self.live_info.band_ids().len() {
+            callback.on_completed();
+            return Err(SchedulerError::NotEnoughBlocks {
+                block_count: self.blocks.len(),
+                band_count: self.live_info.band_ids().len(),
+            });
+        }
+
+        callback.on_started(&SchedulerInfo {
+            count: params.iteration_count,
+        });
+
+        let start = Instant::now();
+        let mut random = Random::new(params.seed);
+
+        let mut current = self.greedy();
+        let mut current_penalty = self.penalty(&current);
+        let mut current_score = self.evaluate(&current, objective);
+        let mut best: Option<(AssignState, u64, f64)> = None;
+
+        for iteration in 0..=params.iteration_count {
+            // 初回は初期解をそのまま評価する
+            if 0 < iteration {
+                if self.blocks.is_empty() || params.time_limit <= start.elapsed() {
+                    break;
+                }
+
+                let Some(candidate) = self.neighbor(&current, &mut random) else {
+                    continue;
+                };
+                let candidate_penalty = self.penalty(&candidate);
+                let candidate_score = self.evaluate(&candidate, objective);
+
+                // 割り当てられなかったバンドの優先度を最優先し、
+                // 同じなら悪化する変更も温度に応じて受け入れる
+                if current_penalty < candidate_penalty {
+                    continue;
+                }
+                if current_penalty == candidate_penalty {
+                    let progress = iteration as f64 / params.iteration_count as f64;
+                    let temperature =
+                        TEMPERATURE_START * (TEMPERATURE_END / TEMPERATURE_START).powf(progress);
+                    let delta = candidate_score - current_score;
+                    if delta < 0.0 && random.next_f64() >= (delta / temperature).exp() {
+                        continue;
+                    }
+                }
+
+                current = candidate;
+                current_penalty = candidate_penalty;
+                current_score = candidate_score;
+            }
+
+            // 全バンドを割り当てられた解だけを結果として通知
+            if !params.allow_partial && !current.bench.is_empty() {
+                continue;
+            }
+            if let Some((_, best_penalty, best_score)) = &best {
+                if (*best_penalty, -best_score) <= (current_penalty, -current_score) {
+                    continue;
+                }
+            }
+
+            self.report(&current, callback);
+            best = Some((current.clone(), current_penalty, current_score));
+        }
+
+        callback.on_completed();
+
+        let Some((state, _, _)) = best else {
+            return Err(SchedulerError::NoFeasibleSchedule);
+        };
+        Ok(self.convert(&state))
+    }
+
+    // 優先度の高いバンド、候補の少ないバンドの順に空いている枠へ詰めていく
+    fn greedy(&self) -> AssignState {
+        let band_ids = self.live_info.band_ids();
+        let mut band_indicies: Vec<usize> = (0..band_ids.len()).collect();
+        band_indicies.sort_by_key(|band_index| {
+            let band_id = band_ids[*band_index];
+            let available_count = self
+                .blocks
+                .iter()
+                .filter(|block_id| self.live_info.confirm_assignable(**block_id, band_id))
+                .count();
+            (
+                Reverse(self.live_info.band_priority(band_id)),
+                available_count,
+            )
+        });
+
+        let mut state = AssignState {
+            slots: vec![None; self.blocks.len()],
+            bench: Vec::default(),
+        };
+        for band_index in band_indicies {
+            let found = (0..self.blocks.len()).find(|block_index| {
+                if state.slots[*block_index].is_some() {
+                    return false;
+                }
+
+                state.slots[*block_index] = Some(band_index);
+                let is_valid = self.is_span_valid(&state, self.block_span_indicies[*block_index])
+                    && self.is_accepted(&state);
+                state.slots[*block_index] = None;
+                is_valid
+            });
+
+            match found {
+                Some(block_index) => state.slots[block_index] = Some(band_index),
+                None => state.bench.push(band_index),
+            }
+        }
+
+        state
+    }
+
+    // ふたつの枠を入れ替えるか、割り当てられなかったバンドと枠を入れ替える
+    fn neighbor(&self, state: &AssignState, random: &mut Random) -> Option<AssignState> {
+        let block_count = self.blocks.len();
+        let lhs = random.next_usize(block_count + state.bench.len());
+        let rhs = random.next_usize(block_count);
+        if lhs == rhs {
+            return None;
+        }
+
+        let mut candidate = state.clone();
+        if lhs < block_count {
+            if candidate.slots[lhs].is_none() && candidate.slots[rhs].is_none() {
+                return None;
+            }
+            candidate.slots.swap(lhs, rhs);
+        } else {
+            let bench_index = lhs - block_count;
+            let band_index = candidate.bench[bench_index];
+            match candidate.slots[rhs].replace(band_index) {
+                Some(removed) => candidate.bench[bench_index] = removed,
+                None => {
+                    candidate.bench.swap_remove(bench_index);
+                }
+            }
+        }
+
+        // 変更した時間帯だけ制約を確認すればよい
+        let is_valid = [lhs, rhs]
+            .iter()
+            .filter(|index| **index < block_count)
+            .all(|index| self.is_span_valid(&candidate, self.block_span_indicies[*index]))
+            && self.is_accepted(&candidate);
+        if is_valid {
+            Some(candidate)
+        } else {
+            None
+        }
+    }
+
+    // 時間帯の中でスケジュールが合っていて、メンバーが衝突していないか
+    fn is_span_valid(&self, state: &AssignState, span_index: usize) -> bool {
+        let mut band_hash_intersect = 0;
+        for block_index in &self.span_block_indicies[span_index] {
+            let Some(band_index) = state.slots[*block_index] else {
+                continue;
+            };
+
+            let band_id = self.live_info.band_ids()[band_index];
+            if !self
+                .live_info
+                .confirm_assignable(self.blocks[*block_index], band_id)
+            {
+                return false;
+            }
+
+            let band_hash = self.live_info.band_hash(band_id).unwrap();
+            if (band_hash_intersect & band_hash) != 0 {
+                return false;
+            }
+            band_hash_intersect |= band_hash;
+        }
+
+        true
+    }
+
+    // 登録された制約をすべて満たしているか
+    // 空き枠は負の値で渡す
+    fn is_accepted(&self, state: &AssignState) -> bool {
+        let indicies: Vec<i32> = self
+            .block_positions
+            .iter()
+            .map(|block_index| state.slots[*block_index].map_or(-1, |band_index| band_index as i32))
+            .collect();
+        matches!(
+            self.constraints
+                .invoke_with_room_matrix(&indicies, self.room_matrix, self.live_info),
+            TraverseOperation::Next
+        )
+    }
+
+    // 割り当てられなかったバンドの優先度の和
+    fn penalty(&self, state: &AssignState) -> u64 {
+        state
+            .bench
+            .iter()
+            .map(|band_index| {
+                let band_id = self.live_info.band_ids()[*band_index];
+                self.live_info.band_priority(band_id) as u64
+            })
+            .sum()
+    }
+
+    fn evaluate(&self, state: &AssignState, objective: &MultiObjective) -> f64 {
+        let table = self.convert(state);
+        let scores = objective.evaluate(&table, self.room_matrix, self.live_info);
+        objective.weighted_sum(&scores)
+    }
+
+    fn report<T: IScheduleCallback>(&self, state: &AssignState, callback: &mut T) {
+        let schedule = self.layout.with_table(self.convert(state));
+        callback.on_assigned(&schedule, self.room_matrix, self.live_info);
+    }
+
+    fn convert(&self, state: &AssignState) -> HashMap<BlockId, BandId> {
+        state
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(block_index, band_index)| {
+                let band_id = self.live_info.band_ids()[(*band_index)?];
+                Some((self.blocks[block_index], band_id))
+            })
+            .collect()
+    }
+}
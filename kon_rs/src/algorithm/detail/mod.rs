@@ -1,11 +1,16 @@
+mod constraint_chain;
+mod local_search;
 mod partial_permutation;
 mod permutation_treverser;
 mod pruning_decorators;
 mod scheduler_impl;
 pub mod util;
 
+pub use constraint_chain::ConstraintChain;
+pub use local_search::{LocalSearchImpl, LocalSearchParams};
 pub use partial_permutation::PartialPermutation;
 pub use pruning_decorators::{
-    BandScheduleTraverseDecorator, MemberConflictTraverseDecorator, TreeTraverser,
+    BandScheduleTraverseDecorator, ITraverseDecorator, MemberConflictTraverseDecorator,
+    TreeTraverser,
 };
 pub use scheduler_impl::SchedulerImpl;
@@ -1,15 +1,6 @@
-use std::ops::Range;
-
 use crate::algorithm::{LiveInfo, RoomMatrix, TraverseOperation};
 
 pub trait ITraverseDecorator {
-    fn invoke(
-        &self,
-        indicies: &[i32],
-        room_assign: &[Range<usize>],
-        live_info: &LiveInfo,
-    ) -> TraverseOperation;
-
     fn invoke_with_room_matrix(
         &self,
         indicies: &[i32],
@@ -22,15 +13,6 @@ pub trait ITraverseDecorator {
 #[derive(Default, Clone)]
 pub struct TreeTraverser;
 impl ITraverseDecorator for TreeTraverser {
-    fn invoke(
-        &self,
-        _data: &[i32],
-        _room_assign: &[Range<usize>],
-        _live_info: &LiveInfo,
-    ) -> TraverseOperation {
-        TraverseOperation::Next
-    }
-
     fn invoke_with_room_matrix(
         &self,
         _indicies: &[i32],
@@ -48,19 +30,6 @@ pub struct BandScheduleTraverseDecorator<T: ITraverseDecorator + Clone> {
 }
 
 impl<T: ITraverseDecorator + Clone> ITraverseDecorator for BandScheduleTraverseDecorator<T> {
-    fn invoke(
-        &self,
-        data: &[i32],
-        room_assign: &[Range<usize>],
-        live_info: &LiveInfo,
-    ) -> TraverseOperation {
-        match self.decorator.invoke(data, room_assign, live_info) {
-            TraverseOperation::Pruning => TraverseOperation::Pruning,
-            TraverseOperation::Skip(index) => TraverseOperation::Skip(index),
-            TraverseOperation::Next => self.invoke_impl(data, room_assign, live_info),
-        }
-    }
-
     fn invoke_with_room_matrix(
         &self,
         indicies: &[i32],
@@ -85,40 +54,6 @@ impl<T: ITraverseDecorator + Clone> BandScheduleTraverseDecorator<T> {
         Self { decorator }
     }
 
-    fn invoke_impl(
-        &self,
-        indicies: &[i32],
-        room_assign: &[Range<usize>],
-        live_info: &LiveInfo,
-    ) -> TraverseOperation {
-        // バンドスケジュールが合致しなかったら走査をやめる
-        for (index, range) in room_assign.iter().enumerate() {
-            for band_index in range.clone().into_iter() {
-                let band_count = live_info.band_ids().len();
-                if band_index >= band_count {
-                    continue;
-                }
-
-                let actual_index = indicies[band_index];
-                if actual_index >= band_count as i32 {
-                    continue;
-                }
-                let band_id = live_info.band_ids()[actual_index as usize];
-
-                let Some(is_available) = live_info.band_schedule(band_id, index as i32) else {
-                    let band_name = live_info.band_name(band_id);
-                    println!("{}", band_name);
-                    panic!();
-                };
-                if !is_available {
-                    return TraverseOperation::Skip(band_index);
-                }
-            }
-        }
-
-        TraverseOperation::Next
-    }
-
     fn invoke_impl_with_room_matrix(
         &self,
         indicies: &[i32],
@@ -127,7 +62,11 @@ impl<T: ITraverseDecorator + Clone> BandScheduleTraverseDecorator<T> {
     ) -> TraverseOperation {
         let mut current_band_index = 0;
         for block_id in room_matrix.blocks() {
-            let actual_index = indicies[current_band_index] as usize;
+            // 空き枠
+            let Ok(actual_index) = usize::try_from(indicies[current_band_index]) else {
+                current_band_index += 1;
+                continue;
+            };
             let band_id = live_info.band_ids()[actual_index];
 
             let is_available = live_info.confirm_assignable(*block_id, band_id);
@@ -156,19 +95,6 @@ pub struct MemberConflictTraverseDecorator<T: ITraverseDecorator> {
 }
 
 impl<T: ITraverseDecorator> ITraverseDecorator for MemberConflictTraverseDecorator<T> {
-    fn invoke(
-        &self,
-        data: &[i32],
-        room_assign: &[Range<usize>],
-        live_info: &LiveInfo,
-    ) -> TraverseOperation {
-        match self.decorator.invoke(data, room_assign, live_info) {
-            TraverseOperation::Pruning => TraverseOperation::Pruning,
-            TraverseOperation::Skip(index) => TraverseOperation::Skip(index),
-            TraverseOperation::Next => self.invoke_impl(data, room_assign, live_info),
-        }
-    }
-
     fn invoke_with_room_matrix(
         &self,
         indicies: &[i32],
@@ -193,42 +119,6 @@ impl<T: ITraverseDecorator> MemberConflictTraverseDecorator<T> {
         Self { decorator }
     }
 
-    fn invoke_impl(
-        &self,
-        indicies: &[i32],
-        room_assign: &[Range<usize>],
-        live_info: &LiveInfo,
-    ) -> TraverseOperation {
-        for range in room_assign {
-            let mut band_hash_intersect = 0;
-            let mut debug_buffer = Vec::default();
-            for band_index in range.clone().into_iter() {
-                // 空き部屋対応
-                let band_count = live_info.band_ids().len();
-                if band_index >= band_count {
-                    continue;
-                }
-                let actual_index = indicies[band_index] as usize;
-                if actual_index >= band_count {
-                    continue;
-                }
-
-                let band_id = live_info.band_ids()[actual_index];
-                let band_hash = live_info.band_hash(band_id).unwrap();
-
-                debug_buffer.push(live_info.band_name(band_id));
-
-                if (band_hash_intersect & band_hash) != 0 {
-                    return TraverseOperation::Skip(band_index);
-                } else {
-                    band_hash_intersect |= band_hash;
-                }
-            }
-        }
-
-        TraverseOperation::Next
-    }
-
     fn invoke_impl_with_room_matrix(
         &self,
         indicies: &[i32],
@@ -239,8 +129,12 @@ impl<T: ITraverseDecorator> MemberConflictTraverseDecorator<T> {
         for span_id in room_matrix.spans() {
             let mut band_hash_intersect = 0;
             for _block_id in room_matrix.iter_span_blocks(*span_id) {
-                let actual_index = indicies[current_band_index];
-                let band_id = live_info.band_ids()[actual_index as usize];
+                // 空き枠
+                let Ok(actual_index) = usize::try_from(indicies[current_band_index]) else {
+                    current_band_index += 1;
+                    continue;
+                };
+                let band_id = live_info.band_ids()[actual_index];
                 let band_hash = live_info.band_hash(band_id).unwrap();
 
                 if (band_hash_intersect & band_hash) == 0 {
@@ -278,7 +172,8 @@ mod tests {
             &HashMap::from([("band_a".to_string(), vec!["a".to_string()])]),
             &HashMap::from([("band_a".to_string(), vec![true])]),
             &room_matrix,
-        );
+        )
+        .unwrap();
 
         let TraverseOperation::Next =
             decorator.invoke_impl_with_room_matrix(&[0], &room_matrix, &live_info)
@@ -302,7 +197,8 @@ mod tests {
                 ("band_b".to_string(), vec![true, true]),
             ]),
             &room_matrix,
-        );
+        )
+        .unwrap();
 
         let TraverseOperation::Next =
             decorator.invoke_impl_with_room_matrix(&[0, 1], &room_matrix, &live_info)
@@ -326,7 +222,8 @@ mod tests {
             &HashMap::from([("band_a".to_string(), vec!["a".to_string()])]),
             &HashMap::from([("band_a".to_string(), vec![false])]),
             &room_matrix,
-        );
+        )
+        .unwrap();
 
         let TraverseOperation::Pruning =
             decorator.invoke_impl_with_room_matrix(&[0], &room_matrix, &live_info)
@@ -344,7 +241,8 @@ mod tests {
             &HashMap::from([("band_a".to_string(), vec!["a".to_string()])]),
             &HashMap::from([("band_a".to_string(), vec![false])]),
             &room_matrix,
-        );
+        )
+        .unwrap();
 
         let TraverseOperation::Next =
             decorator.invoke_impl_with_room_matrix(&[0], &room_matrix, &live_info)
@@ -368,7 +266,8 @@ mod tests {
                 ("band_b".to_string(), vec![true]),
             ]),
             &room_matrix,
-        );
+        )
+        .unwrap();
 
         let TraverseOperation::Pruning =
             decorator.invoke_impl_with_room_matrix(&[0, 1], &room_matrix, &live_info)
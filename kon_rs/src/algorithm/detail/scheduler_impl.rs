@@ -5,8 +5,11 @@ use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 
-use crate::algorithm::{IScheduleCallback, LiveInfo, RoomMatrix, SchedulerInfo, TraverseOperation};
-use crate::{BandId, BlockId, RoomId};
+use crate::algorithm::{
+    IScheduleCallback, LiveInfo, RoomMatrix, Schedule, SchedulerError, SchedulerInfo,
+    TraverseOperation,
+};
+use crate::{BandId, BlockId};
 
 use super::permutation_treverser::PermutationTraverser;
 use super::pruning_decorators::ITraverseDecorator;
@@ -36,12 +39,15 @@ where
         &mut self,
         room_matrix: &RoomMatrix,
         live_info: &LiveInfo,
-    ) -> Result<HashMap<BandId, BlockId>, ()> {
+    ) -> Result<(), SchedulerError> {
         // そもそも部屋数が足りてなければ失敗
         let available_rooms = room_matrix.blocks().len();
         if available_rooms < live_info.band_ids().len() {
             self.callback.on_completed();
-            return Err(());
+            return Err(SchedulerError::NotEnoughBlocks {
+                block_count: available_rooms,
+                band_count: live_info.band_ids().len(),
+            });
         }
 
         // スケジュールの全組み合わせを調査
@@ -54,6 +60,8 @@ where
             count: util::factional(room_matrix.blocks().len()),
         });
 
+        // 部屋割りとバンドの情報は走査中に変わらないので一度だけ作る
+        let layout = Schedule::new(HashMap::default(), room_matrix, live_info);
         while let Some(permutation) = sub_tree.next() {
             let traverse_operation = self.decorator.invoke_with_room_matrix(
                 permutation.current(),
@@ -64,7 +72,8 @@ where
             match traverse_operation {
                 TraverseOperation::Next => {
                     let table = Self::convert(permutation.current(), room_matrix, live_info);
-                    self.callback.on_assigned(&table, room_matrix, live_info);
+                    let schedule = layout.with_table(table);
+                    self.callback.on_assigned(&schedule, room_matrix, live_info);
                 }
                 TraverseOperation::Pruning => {
                     break;
@@ -75,14 +84,14 @@ where
 
         self.callback.on_completed();
 
-        Ok(Default::default())
+        Ok(())
     }
 
     pub async fn assign_async(
         &mut self,
         room_matrix: Arc<RoomMatrix>,
         live_info: Arc<LiveInfo>,
-    ) -> Result<HashMap<BandId, RoomId>, ()> {
+    ) -> Result<(), SchedulerError> {
         self.assign_async_with_params(
             room_matrix,
             live_info,
@@ -98,11 +107,14 @@ where
         live_info: Arc<LiveInfo>,
         partial_tree_depth: usize,
         task_count_max: usize,
-    ) -> Result<HashMap<BandId, RoomId>, ()> {
+    ) -> Result<(), SchedulerError> {
         // そもそも部屋数が足りてなければ失敗
         let available_rooms = room_matrix.blocks().len();
         if available_rooms < live_info.band_ids().len() {
-            return Err(());
+            return Err(SchedulerError::NotEnoughBlocks {
+                block_count: available_rooms,
+                band_count: live_info.band_ids().len(),
+            });
         }
 
         // スケジュールの全組み合わせを調査
@@ -119,6 +131,8 @@ where
             count: util::factional(room_matrix.blocks().len()),
         });
 
+        // 部屋割りとバンドの情報は走査中に変わらないので一度だけ作る
+        let layout = Schedule::new(HashMap::default(), &room_matrix, &live_info);
         let mut task_queue = TaskQueue::new(task_count_max);
         while let Some(mut sub_tree) = traverer.allocate() {
             let decorator_local = self.decorator.clone();
@@ -144,30 +158,34 @@ where
                 return results;
             });
 
-            let results = task_queue.push_task(handle).await;
+            let results = task_queue.push_task(handle).await?;
             {
                 for result in results {
                     for permutation in result {
                         let table = Self::convert(permutation.current(), &room_matrix, &live_info);
-                        self.callback.on_assigned(&table, &room_matrix, &live_info);
+                        let schedule = layout.with_table(table);
+                        self.callback
+                            .on_assigned(&schedule, &room_matrix, &live_info);
                     }
                 }
             }
         }
 
-        let results = task_queue.wait().await;
+        let results = task_queue.wait().await?;
         {
             for result in results {
                 for permutation in result {
                     let table = Self::convert(permutation.current(), &room_matrix, &live_info);
-                    self.callback.on_assigned(&table, &room_matrix, &live_info);
+                    let schedule = layout.with_table(table);
+                    self.callback
+                        .on_assigned(&schedule, &room_matrix, &live_info);
                 }
             }
         }
 
         self.callback.on_completed();
 
-        Ok(Default::default())
+        Ok(())
     }
 
     fn convert(
@@ -199,22 +217,22 @@ impl<T> TaskQueue<T> {
         }
     }
 
-    pub async fn push_task(&mut self, handle: JoinHandle<T>) -> Vec<T> {
+    pub async fn push_task(&mut self, handle: JoinHandle<T>) -> Result<Vec<T>, SchedulerError> {
         if self.tasks.len() < self.task_count_max {
             self.tasks.push(handle);
-            return Vec::default();
+            return Ok(Vec::default());
         }
 
-        let results = self.wait_until(self.task_count_max).await;
+        let results = self.wait_until(self.task_count_max).await?;
         self.tasks.push(handle);
-        results
+        Ok(results)
     }
 
-    pub async fn wait(&mut self) -> Vec<T> {
+    pub async fn wait(&mut self) -> Result<Vec<T>, SchedulerError> {
         self.wait_until(0).await
     }
 
-    async fn wait_until(&mut self, count: usize) -> Vec<T> {
+    async fn wait_until(&mut self, count: usize) -> Result<Vec<T>, SchedulerError> {
         let mut results = Vec::default();
         while count < self.tasks.len() {
             tokio::time::sleep(Duration::from_millis(50)).await;
@@ -225,10 +243,15 @@ impl<T> TaskQueue<T> {
                 }
 
                 let finished_task = self.tasks.swap_remove(index);
-                results.push(finished_task.await.unwrap());
+                let result = finished_task
+                    .await
+                    .map_err(|error| SchedulerError::TaskFailed {
+                        message: error.to_string(),
+                    })?;
+                results.push(result);
             }
         }
 
-        results
+        Ok(results)
     }
 }
@@ -0,0 +1,248 @@
+use crate::UserId;
+
+use super::{ConcertTimetable, LiveInfo};
+
+/// 出演ひとつ分の時間の係の担当
+#[derive(Debug, Clone, PartialEq)]
+pub struct DutyAssignment {
+    /// ConcertTimetable::slots() のインデックス
+    pub slot_index: usize,
+
+    /// 係の名前
+    pub role: String,
+
+    /// 担当するメンバーの識別子。担当できるメンバーがいなければ None
+    pub member: Option<String>,
+}
+
+/// ライブ当日の係の当番表
+pub struct DutyRoster {
+    assignments: Vec<DutyAssignment>,
+}
+
+impl DutyRoster {
+    /// 出演順、係の順に並んだ担当
+    pub fn assignments(&self) -> &[DutyAssignment] {
+        &self.assignments
+    }
+
+    /// 出演ひとつ分の時間の担当
+    pub fn slot(&self, slot_index: usize) -> impl Iterator<Item = &DutyAssignment> {
+        self.assignments
+            .iter()
+            .filter(move |assignment| assignment.slot_index == slot_index)
+    }
+
+    /// メンバーが担当する係
+    pub fn member(&self, identifier: &str) -> impl Iterator<Item = &DutyAssignment> + '_ {
+        let identifier = identifier.to_string();
+        self.assignments
+            .iter()
+            .filter(move |assignment| assignment.member.as_deref() == Some(identifier.as_str()))
+    }
+
+    /// 担当できるメンバーがいなかった係
+    pub fn shortages(&self) -> Vec<&DutyAssignment> {
+        self.assignments
+            .iter()
+            .filter(|assignment| assignment.member.is_none())
+            .collect()
+    }
+}
+
+/// PA や照明、受付などの係を出演していないメンバーに割り当てます
+///
+/// 出演中のメンバーと、その前後の出演に出るメンバーは係から外します。
+/// 係を担当する時間がメンバー間で偏らないように、担当時間が短いメンバーから順に割り当てます。
+pub struct DutyRosterPlanner {
+    // 係の名前と必要な人数
+    roles: Vec<(String, usize)>,
+
+    // 出演の前後で係から外す出演の数
+    rest_slot_count: usize,
+}
+
+impl DutyRosterPlanner {
+    pub fn new() -> Self {
+        Self {
+            roles: Vec::default(),
+            rest_slot_count: 1,
+        }
+    }
+
+    /// 係を追加します
+    pub fn push_role(mut self, role: &str, count: usize) -> Self {
+        self.roles.push((role.to_string(), count));
+        self
+    }
+
+    /// 出演の前後で係から外す出演の数
+    ///
+    /// 0 なら出演の直前・直後でも係を担当します。
+    pub fn with_rest_slot_count(mut self, rest_slot_count: usize) -> Self {
+        self.rest_slot_count = rest_slot_count;
+        self
+    }
+
+    /// 当番表を作ります
+    ///
+    /// 出演バンドは live_info のバンド名で照合します。
+    pub fn plan(&self, timetable: &ConcertTimetable, live_info: &LiveInfo) -> DutyRoster {
+        let user_ids = live_info.user_ids();
+        let slots = timetable.slots();
+
+        // 出演 -> 出演するメンバー
+        let performers: Vec<Vec<UserId>> = slots
+            .iter()
+            .map(|slot| {
+                live_info
+                    .band_ids()
+                    .iter()
+                    .find(|id| live_info.band_name(**id) == Some(slot.band_name.as_str()))
+                    .and_then(|id| live_info.band_member_ids(*id))
+                    .unwrap_or_default()
+                    .to_vec()
+            })
+            .collect();
+
+        // メンバーごとの担当時間 (分) と担当回数
+        let mut workloads: Vec<(i64, usize)> = vec![(0, 0); user_ids.len()];
+
+        let mut assignments = Vec::default();
+        for (slot_index, slot) in slots.iter().enumerate() {
+            let minutes = slot.time.end.minutes_since(&slot.time.start);
+
+            // 前後の出演も含めて出演するメンバーは外す
+            let first = slot_index.saturating_sub(self.rest_slot_count);
+            let last = (slot_index + self.rest_slot_count).min(slots.len() - 1);
+            let mut is_busy: Vec<bool> = user_ids
+                .iter()
+                .map(|id| performers[first..=last].iter().any(|x| x.contains(id)))
+                .collect();
+
+            for (role, count) in &self.roles {
+                for _ in 0..*count {
+                    // 担当時間、担当回数の少ないメンバーを優先
+                    let candidate = (0..user_ids.len())
+                        .filter(|index| !is_busy[*index])
+                        .min_by_key(|index| workloads[*index]);
+
+                    let member = candidate.map(|index| {
+                        is_busy[index] = true;
+                        workloads[index].0 += minutes;
+                        workloads[index].1 += 1;
+                        live_info
+                            .user_identifier(user_ids[index])
+                            .unwrap_or_default()
+                            .to_string()
+                    });
+                    assignments.push(DutyAssignment {
+                        slot_index,
+                        role: role.clone(),
+                        member,
+                    });
+                }
+            }
+        }
+
+        DutyRoster { assignments }
+    }
+}
+
+impl Default for DutyRosterPlanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithm::tests::create_live;
+    use crate::algorithm::{ConcertSetting, ConcertTimetable, LocalDateTime};
+    use crate::LiveProgram;
+
+    use super::DutyRosterPlanner;
+
+    fn create_timetable() -> ConcertTimetable {
+        let program = LiveProgram {
+            user_names: Vec::default(),
+            item: ["band_a", "band_b", "band_c", "band_d"]
+                .iter()
+                .map(|x| x.to_string())
+                .collect(),
+        };
+        program.create_timetable(&ConcertSetting::new(LocalDateTime::new(2024, 4, 6, 17, 0)))
+    }
+
+    #[test]
+    fn roster() {
+        // e と f は出演しない
+        let live_info = create_live(&[
+            ("band_a", &["a"]),
+            ("band_b", &["b"]),
+            ("band_c", &["c"]),
+            ("band_d", &["d"]),
+            ("band_x", &["e", "f"]),
+        ]);
+        let roster = DutyRosterPlanner::new()
+            .push_role("PA", 1)
+            .push_role("照明", 1)
+            .plan(&create_timetable(), &live_info);
+        assert_eq!(roster.assignments().len(), 8);
+        assert!(roster.shortages().is_empty());
+
+        // 出演中と前後の出演のメンバーは担当しない
+        for (slot_index, busy) in [
+            (0, ["a", "b", "-"]),
+            (1, ["a", "b", "c"]),
+            (2, ["b", "c", "d"]),
+            (3, ["c", "d", "-"]),
+        ] {
+            let members: Vec<&str> = roster
+                .slot(slot_index)
+                .map(|x| x.member.as_deref().unwrap())
+                .collect();
+            assert_eq!(members.len(), 2);
+            assert_ne!(members[0], members[1]);
+            assert!(members.iter().all(|x| !busy.contains(x)));
+        }
+
+        // 全員が 1 回か 2 回担当する
+        for member in ["a", "b", "c", "d", "e", "f"] {
+            let count = roster.member(member).count();
+            assert!((1..=2).contains(&count));
+        }
+    }
+
+    #[test]
+    fn shortage() {
+        let live_info = create_live(&[
+            ("band_a", &["a"]),
+            ("band_b", &["b"]),
+            ("band_c", &["c"]),
+            ("band_d", &["d"]),
+        ]);
+        let roster = DutyRosterPlanner::new()
+            .push_role("受付", 1)
+            .plan(&create_timetable(), &live_info);
+
+        // 2 番目と 3 番目の出演は前後の出演と合わせて 3 人が出演するので、残りのひとりが担当する
+        assert!(roster.shortages().is_empty());
+        assert_eq!(roster.slot(1).next().unwrap().member.as_deref(), Some("d"));
+        assert_eq!(roster.slot(2).next().unwrap().member.as_deref(), Some("a"));
+
+        // ふたり必要だと足りない
+        let roster = DutyRosterPlanner::new()
+            .push_role("受付", 2)
+            .plan(&create_timetable(), &live_info);
+        let shortages: Vec<usize> = roster.shortages().iter().map(|x| x.slot_index).collect();
+        assert_eq!(shortages, vec![1, 2]);
+
+        // 前後の出演も担当できるなら足りる
+        let roster = DutyRosterPlanner::new()
+            .push_role("受付", 2)
+            .with_rest_slot_count(0)
+            .plan(&create_timetable(), &live_info);
+        assert!(roster.shortages().is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+
+/// LiveInfo を構築できなかった理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LiveInfoError {
+    /// スケジュール表にバンド一覧にないバンドが含まれている
+    UnknownBand { band_name: String },
+
+    /// バンドのスケジュールが登録されていない
+    MissingSchedule { band_name: String },
+
+    /// スケジュールの長さが時間帯の数より短い
+    ScheduleTooShort {
+        band_name: String,
+        length: usize,
+        span_count: usize,
+    },
+
+    /// バンドにメンバーがひとりもいない
+    MissingMember { band_name: String },
+
+    /// メンバーが多すぎてバンドのハッシュ値で表現できない
+    TooManyUsers { user_count: usize },
+}
+
+impl fmt::Display for LiveInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiveInfoError::UnknownBand { band_name } => {
+                write!(f, "unknown band in schedule table: {band_name}")
+            }
+            LiveInfoError::MissingSchedule { band_name } => {
+                write!(f, "schedule is missing: {band_name}")
+            }
+            LiveInfoError::ScheduleTooShort {
+                band_name,
+                length,
+                span_count,
+            } => write!(
+                f,
+                "schedule of {band_name} has {length} entries but there are {span_count} spans"
+            ),
+            LiveInfoError::MissingMember { band_name } => {
+                write!(f, "band has no member: {band_name}")
+            }
+            LiveInfoError::TooManyUsers { user_count } => {
+                write!(f, "too many users: {user_count} (max {})", u64::BITS)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LiveInfoError {}
+
+/// スケジューリングに失敗した理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchedulerError {
+    /// 枠の数がバンドの数より少ない
+    ///
+    /// 一部のバンドだけを割り当てるなら Scheduler::assign_partial か LocalSearchScheduler::assign_partial を使ってください。
+    NotEnoughBlocks {
+        block_count: usize,
+        band_count: usize,
+    },
+
+    /// 制約を満たすスケジュールが見つからなかった
+    NoFeasibleSchedule,
+
+    /// 並列実行中のタスクが異常終了した
+    TaskFailed { message: String },
+
+    /// ひとつの部屋に並べる枠が多すぎる。部屋ごとの枠は 255 まで
+    TooManyBlocks { block_count: usize },
+
+    /// 部屋割りにない部屋が指定された
+    UnknownRoom,
+
+    /// 部屋割りにない時間帯が指定された
+    UnknownSpan,
+}
+
+impl fmt::Display for SchedulerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchedulerError::NotEnoughBlocks {
+                block_count,
+                band_count,
+            } => write!(
+                f,
+                "not enough blocks: {block_count} blocks for {band_count} bands"
+            ),
+            SchedulerError::NoFeasibleSchedule => write!(f, "no feasible schedule found"),
+            SchedulerError::TaskFailed { message } => write!(f, "task failed: {message}"),
+            SchedulerError::TooManyBlocks { block_count } => {
+                write!(f, "too many blocks in a room: {block_count}")
+            }
+            SchedulerError::UnknownRoom => write!(f, "unknown room"),
+            SchedulerError::UnknownSpan => write!(f, "unknown span"),
+        }
+    }
+}
+
+impl std::error::Error for SchedulerError {}
@@ -1,58 +1,107 @@
 use std::collections::HashMap;
 
-use crate::{BandId, RoomId};
+use crate::{BandId, BlockId, RoomId, UserId};
 
-use super::LiveInfo;
+use super::objective::{create_band_hash_table, create_room_assign};
+use super::{LiveInfo, RoomMatrix};
+
+/// メンバーが出番の間に待つ時間帯の数
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IdleTime {
+    /// 出番の間の待ち時間の合計
+    pub total: u32,
+
+    /// 最も長い待ち時間
+    pub max: u32,
+}
+
+impl IdleTime {
+    /// 出番の時間帯のインデックスから待ち時間を求めます
+    pub fn from_spans(spans: &[usize]) -> Self {
+        let mut spans = spans.to_vec();
+        spans.sort();
+        spans.dedup();
+
+        let mut idle_time = Self::default();
+        for span in spans.windows(2) {
+            let gap = (span[1] - span[0] - 1) as u32;
+            idle_time.total += gap;
+            idle_time.max = idle_time.max.max(gap);
+        }
+        idle_time
+    }
+}
 
 pub struct Evaluator;
 
 impl Evaluator {
-    pub fn evaluate(rooms: &[u32], indicies: &[usize], live_info: &LiveInfo) -> u32 {
-        let max = *rooms.iter().max().unwrap() as usize;
-        let offsets: Vec<usize> = rooms
+    // 同じ部屋で連続する枠に共通するメンバーの数
+    // スケジューラーの結果をそのまま評価する
+    pub fn evaluate(
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> u32 {
+        let room_assign = create_room_assign(table, room_matrix);
+        let band_hash_table = create_band_hash_table(live_info);
+        Self::evaluate_user_coherency(&room_assign, &band_hash_table)
+    }
+
+    // メンバーごとの待ち時間
+    // 出番がひとつ以下のメンバーは待ち時間 0 とする
+    pub fn evaluate_member_idle_time(
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> HashMap<UserId, IdleTime> {
+        let mut span_table: HashMap<UserId, Vec<usize>> = live_info
+            .user_ids()
             .iter()
-            .scan(0usize, |offset, room_count| {
-                *offset += *room_count as usize;
-                Some(*offset)
-            })
+            .map(|id| (*id, Vec::default()))
             .collect();
-
-        let mut score = 0;
-        // 時間軸方向に走査
-        for time_index in 0usize..(rooms.len() - 1) {
-            for room_index in 0..max {
-                let offset = offsets[time_index];
-                let Some(global_index_before) = indicies.get(offset + room_index) else {
-                    continue;
-                };
-
-                let offset = offsets[time_index + 1];
-                let Some(global_index_after) = indicies.get(offset + room_index) else {
-                    continue;
-                };
-
-                let Some(id_after) = live_info.band_ids().get(*global_index_before) else {
-                    continue;
-                };
-
-                let Some(id_before) = live_info.band_ids().get(*global_index_after) else {
-                    continue;
-                };
-
-                let hash_after = live_info.band_hash(*id_after).unwrap();
-                let hash_before = live_info.band_hash(*id_before).unwrap();
-                let bits = hash_after & hash_before;
-                score += bits.count_ones();
+        for (block_id, band_id) in table {
+            let Some((span_index, _)) = room_matrix.block_location(*block_id) else {
+                continue;
+            };
+            let Some(member_ids) = live_info.band_member_ids(*band_id) else {
+                continue;
+            };
+            for member_id in member_ids {
+                span_table.entry(*member_id).or_default().push(span_index);
             }
         }
 
-        score
+        span_table
+            .into_iter()
+            .map(|(id, spans)| (id, IdleTime::from_spans(&spans)))
+            .collect()
     }
 
     // 部屋をどれくらい使い切れてるかの判定
     // 点数が高いほど優秀な部屋割り
-    pub fn evaluate_room_density(_room_assign: &HashMap<RoomId, Vec<BandId>>) -> i32 {
-        0
+    // 空き枠が部屋の最後にまとまっていれば早めに部屋を返せるので、
+    // 後ろにバンドが入っている空き枠の数だけ減点する
+    pub fn evaluate_room_density(
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+    ) -> i32 {
+        let mut score = 0;
+        for room_id in room_matrix.rooms() {
+            // 後ろから走査して、バンドが入った枠より前にある空き枠を数える
+            let block_ids: Vec<&BlockId> = room_matrix.iter_room_blocks(*room_id).collect();
+            let mut is_used_after = false;
+            for block_id in block_ids.into_iter().rev() {
+                let is_used = table
+                    .get(block_id)
+                    .is_some_and(|band_id| *band_id != BandId::invalid());
+                if is_used {
+                    is_used_after = true;
+                } else if is_used_after {
+                    score -= 1;
+                }
+            }
+        }
+        score
     }
 
     // 部屋移動の手間の少なさを判定
@@ -64,8 +113,8 @@ impl Evaluator {
         let mut score = 0;
         for band_ids in room_assign.values() {
             for (index, band_id) in band_ids.iter().skip(1).enumerate() {
-                // イテレーターを 1 スキップしてるので 1 足すと実際のインデックスになる
-                let previous_band_id = band_ids[index + 1];
+                // イテレーターを 1 スキップしてるので index がひとつ前のバンドのインデックスになる
+                let previous_band_id = band_ids[index];
                 let previous_band_hash = band_hash_table.get(&previous_band_id).unwrap();
                 let band_hash = band_hash_table.get(band_id).unwrap();
                 let coherency = (previous_band_hash & band_hash).count_ones();
@@ -80,9 +129,12 @@ impl Evaluator {
 mod tests {
     use std::collections::HashMap;
 
-    use crate::{BandId, RoomId};
+    use crate::{
+        algorithm::{create_live_info, RoomMatrix},
+        BandId, RoomId,
+    };
 
-    use super::Evaluator;
+    use super::{Evaluator, IdleTime};
 
     #[test]
     fn empty() {
@@ -112,21 +164,172 @@ mod tests {
         assert!(0 < score);
     }
 
+    // スケジューラーの結果から同じ部屋で連続するメンバーを数える
     #[test]
-    fn simple() {
+    fn evaluate_table() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string(), "b".to_string()]),
+            ("band_b".to_string(), vec!["a".to_string(), "b".to_string()]),
+            ("band_c".to_string(), vec!["c".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 3]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(3).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let blocks: Vec<_> = room_matrix
+            .iter_room_blocks(room_matrix.rooms()[0])
+            .copied()
+            .collect();
+        let band_ids = live_info.band_ids();
+
+        // band_a と band_b が続くと a と b が部屋に残れる
+        let table = HashMap::from([
+            (blocks[0], band_ids[0]),
+            (blocks[1], band_ids[1]),
+            (blocks[2], band_ids[2]),
+        ]);
+        assert_eq!(Evaluator::evaluate(&table, &room_matrix, &live_info), 2);
+
+        // 間に band_c が入ると連続しない
+        let table = HashMap::from([
+            (blocks[0], band_ids[0]),
+            (blocks[1], band_ids[2]),
+            (blocks[2], band_ids[1]),
+        ]);
+        assert_eq!(Evaluator::evaluate(&table, &room_matrix, &live_info), 0);
+    }
+
+    #[test]
+    fn idle_time() {
+        assert_eq!(IdleTime::from_spans(&[]), IdleTime::default());
+        assert_eq!(IdleTime::from_spans(&[3]), IdleTime::default());
+        assert_eq!(
+            IdleTime::from_spans(&[5, 0, 1, 3]),
+            IdleTime { total: 2, max: 1 }
+        );
+        assert_eq!(
+            IdleTime::from_spans(&[0, 4, 4]),
+            IdleTime { total: 3, max: 3 }
+        );
+    }
+
+    // 朝一番と最後の枠に入ったメンバーは間の時間帯を待つ
+    #[test]
+    fn member_idle_time() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string(), "b".to_string()]),
+            ("band_b".to_string(), vec!["a".to_string()]),
+            ("band_c".to_string(), vec!["c".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 4]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(4).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let blocks: Vec<_> = room_matrix
+            .iter_room_blocks(room_matrix.rooms()[0])
+            .copied()
+            .collect();
+        let band_ids = live_info.band_ids();
+        let table = HashMap::from([
+            (blocks[0], band_ids[0]),
+            (blocks[1], band_ids[2]),
+            (blocks[3], band_ids[1]),
+        ]);
+
+        let idle_time_table =
+            Evaluator::evaluate_member_idle_time(&table, &room_matrix, &live_info);
+        let find = |identifier: &str| {
+            let (_, idle_time) = idle_time_table
+                .iter()
+                .find(|(id, _)| live_info.user_identifier(**id) == Some(identifier))
+                .unwrap();
+            *idle_time
+        };
+        assert_eq!(find("a"), IdleTime { total: 2, max: 2 });
+        assert_eq!(find("b"), IdleTime::default());
+        assert_eq!(find("c"), IdleTime::default());
+    }
+
+    // 連続するバンドにメンバーの重複がなければ一貫性はない
+    // 以前はバンドを自分自身と比べていたので、メンバーの数だけ加点していた
+    #[test]
+    fn different_band_coherency() {
         let band_id0 = BandId::new();
         let band_id1 = BandId::new();
+        let room_assign = HashMap::from([(RoomId::new(), vec![band_id0, band_id1])]);
+        let band_table = HashMap::from([(band_id0, 0x0003u64), (band_id1, 0x000Cu64)]);
+        let score = Evaluator::evaluate_user_coherency(&room_assign, &band_table);
+        assert_eq!(score, 0);
+
+        // 重複するのは band_id1 と band_id2 の間のひとりだけ
         let band_id2 = BandId::new();
+        let room_assign = HashMap::from([(RoomId::new(), vec![band_id0, band_id1, band_id2])]);
+        let band_table = HashMap::from([
+            (band_id0, 0x0003u64),
+            (band_id1, 0x000Cu64),
+            (band_id2, 0x0030u64 | 0x0004u64),
+        ]);
+        let score = Evaluator::evaluate_user_coherency(&room_assign, &band_table);
+        assert_eq!(score, 1);
+    }
+
+    #[test]
+    fn simple() {
+        let band_id0 = BandId::new();
+        let band_id1 = BandId::new();
+
+        // 1 部屋 3 枠
+        let room_matrix = RoomMatrix::builder().push_room(3).build();
+        let blocks: Vec<_> = room_matrix
+            .iter_room_blocks(room_matrix.rooms()[0])
+            .copied()
+            .collect();
+
+        // 同じ部屋割りなら空き時間は最後に詰まってる方が優秀
+        let good_table = HashMap::from([(blocks[0], band_id0), (blocks[1], band_id1)]);
+        let bad_table = HashMap::from([(blocks[0], band_id0), (blocks[2], band_id1)]);
+        let worse_table = HashMap::from([(blocks[2], band_id1)]);
+
+        let good_room_assign_score = Evaluator::evaluate_room_density(&good_table, &room_matrix);
+        let bad_room_assign_score = Evaluator::evaluate_room_density(&bad_table, &room_matrix);
+        let worse_room_assign_score = Evaluator::evaluate_room_density(&worse_table, &room_matrix);
+        assert_eq!(good_room_assign_score, 0);
+        assert!(bad_room_assign_score < good_room_assign_score);
+        assert!(worse_room_assign_score < bad_room_assign_score);
+    }
+
+    // 部屋ごとに空き枠の位置を判定する
+    #[test]
+    fn room_density_per_room() {
+        let band_id = BandId::new();
+
+        // 1 枠の部屋と 2 枠の部屋
+        let room_matrix = RoomMatrix::builder().push_room(1).push_room(2).build();
+        let room0: Vec<_> = room_matrix
+            .iter_room_blocks(room_matrix.rooms()[0])
+            .copied()
+            .collect();
+        let room1: Vec<_> = room_matrix
+            .iter_room_blocks(room_matrix.rooms()[1])
+            .copied()
+            .collect();
 
-        // 同じ部屋割りなら空き時間は詰まってる方が優秀
-        let good_room_assign =
-            HashMap::from([(RoomId::new(), vec![BandId::invalid(), band_id2, band_id1])]);
-        let bad_room_assign =
-            HashMap::from([(RoomId::new(), vec![band_id0, BandId::invalid(), band_id1])]);
+        // 空いた部屋は丸ごと返せる
+        let table = HashMap::from([(room1[0], band_id), (room1[1], band_id)]);
+        assert_eq!(Evaluator::evaluate_room_density(&table, &room_matrix), 0);
 
-        // 未実装なので一旦コメントアウト
-        let _good_room_assign_score = Evaluator::evaluate_room_density(&good_room_assign);
-        let _bad_room_assign_score = Evaluator::evaluate_room_density(&bad_room_assign);
-        // assert!(good_room_assign_score < bad_room_assign_score);
+        // 空き枠として明示されたバンドも空きとして扱う
+        let table = HashMap::from([
+            (room0[0], band_id),
+            (room1[0], BandId::invalid()),
+            (room1[1], band_id),
+        ]);
+        assert_eq!(Evaluator::evaluate_room_density(&table, &room_matrix), -1);
     }
 }
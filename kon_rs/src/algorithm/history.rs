@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BandId, BlockId};
+
+use super::{LiveInfo, RoomMatrix};
+
+// バンド名 -> (時間帯のインデックス, 部屋のインデックス)
+type SessionAssignTable = HashMap<String, (usize, usize)>;
+
+/// 過去の練習会での割り当て
+///
+/// 部屋や時間帯の識別子は練習会ごとに作り直されるので、バンド名と何番目の時間帯・部屋かで記録します。
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AssignHistory {
+    // 練習会 ("第N回" など) ごとの割り当て
+    sessions: Vec<(String, SessionAssignTable)>,
+}
+
+impl AssignHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 練習会で確定したスケジュールを記録します
+    ///
+    /// 同じ練習会をもう一度記録すると上書きします。
+    pub fn push(
+        &mut self,
+        session: &str,
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) {
+        let assign_table = table
+            .iter()
+            .filter_map(|(block_id, band_id)| {
+                let location = room_matrix.block_location(*block_id)?;
+                Some((live_info.band_name(*band_id)?.to_string(), location))
+            })
+            .collect();
+
+        self.sessions.retain(|(name, _)| name != session);
+        self.sessions.push((session.to_string(), assign_table));
+    }
+
+    /// 記録済みの練習会
+    pub fn sessions(&self) -> impl Iterator<Item = &str> {
+        self.sessions.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// バンドが指定の時間帯に割り当てられた回数
+    pub fn span_count(&self, band_name: &str, span_index: usize) -> usize {
+        self.sessions
+            .iter()
+            .filter(|(_, table)| {
+                table
+                    .get(band_name)
+                    .is_some_and(|(span, _)| *span == span_index)
+            })
+            .count()
+    }
+
+    /// バンドが指定の部屋に割り当てられた回数
+    pub fn room_count(&self, band_name: &str, room_index: usize) -> usize {
+        self.sessions
+            .iter()
+            .filter(|(_, table)| {
+                table
+                    .get(band_name)
+                    .is_some_and(|(_, room)| *room == room_index)
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::algorithm::{create_live_info, RoomMatrix};
+
+    use super::AssignHistory;
+
+    #[test]
+    fn simple() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 2]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(2).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let room_id = room_matrix.rooms()[0];
+        let blocks: Vec<_> = room_matrix.iter_room_blocks(room_id).copied().collect();
+        let band_ids = live_info.band_ids();
+
+        let mut history = AssignHistory::new();
+        let table = HashMap::from([(blocks[0], band_ids[0]), (blocks[1], band_ids[1])]);
+        history.push("第1回", &table, &room_matrix, &live_info);
+        history.push("第2回", &table, &room_matrix, &live_info);
+        assert_eq!(history.span_count("band_a", 0), 2);
+        assert_eq!(history.span_count("band_b", 1), 2);
+        assert_eq!(history.room_count("band_a", 0), 2);
+
+        // 同じ練習会は上書き
+        let table = HashMap::from([(blocks[0], band_ids[1]), (blocks[1], band_ids[0])]);
+        history.push("第2回", &table, &room_matrix, &live_info);
+        assert_eq!(history.sessions().count(), 2);
+        assert_eq!(history.span_count("band_a", 0), 1);
+        assert_eq!(history.span_count("band_a", 1), 1);
+    }
+}
@@ -0,0 +1,387 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::detail::{ConstraintChain, LocalSearchImpl, LocalSearchParams};
+use super::{
+    IConstraint, IScheduleCallback, LiveInfo, MultiObjective, RoomMatrix, Schedule, SchedulerError,
+    SchedulerInfo, TaskId, TaskInfo, UserCoherencyObjective,
+};
+
+/// 全探索できない規模のライブ向けに、焼きなまし法で準最適なスケジュールを探索します
+///
+/// 貪欲法で作った初期解から枠の入れ替えを繰り返し、制約を守ったまま MultiObjective の評価値を改善します。
+/// 評価値が更新されるたびに IScheduleCallback::on_assigned で通知します。
+pub struct LocalSearchScheduler<T> {
+    callback: T,
+
+    // 最大化する評価値
+    objective: MultiObjective,
+
+    // スケジュールと衝突の確認に加えて適用する制約
+    constraints: ConstraintChain,
+
+    // 探索を打ち切る時間
+    time_limit: Duration,
+
+    // 探索の反復回数
+    iteration_count: usize,
+
+    // 乱数のシード値
+    seed: u64,
+}
+
+impl LocalSearchScheduler<()> {
+    pub fn new() -> Self {
+        Self::new_impl(())
+    }
+
+    /// 見つかった中で最も評価の高いスケジュールを返します
+    pub fn assign(
+        &self,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> Result<Schedule, SchedulerError> {
+        let table = LocalSearchImpl::new(room_matrix, live_info, &self.constraints).run(
+            &self.objective,
+            &self.params(false),
+            &mut EmptyCallback,
+        )?;
+        Ok(Schedule::new(table, room_matrix, live_info))
+    }
+
+    /// 全バンドを割り当てられない場合は、優先度の高いバンドからなるべく多く割り当てます
+    ///
+    /// 割り当てられなかったバンドは Schedule::unassigned_band_ids で取得できます。
+    pub fn assign_partial(&self, room_matrix: &RoomMatrix, live_info: &LiveInfo) -> Schedule {
+        Self::assign_partial_impl(
+            room_matrix,
+            live_info,
+            &self.objective,
+            &self.constraints,
+            &self.params(true),
+            &mut EmptyCallback,
+        )
+    }
+}
+
+impl<T> LocalSearchScheduler<T>
+where
+    T: IScheduleCallback,
+{
+    pub fn new_with_callback(callback: T) -> Self {
+        Self::new_impl(callback)
+    }
+
+    pub fn assign(
+        &mut self,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> Result<Schedule, SchedulerError> {
+        let params = self.params(false);
+        let table = LocalSearchImpl::new(room_matrix, live_info, &self.constraints).run(
+            &self.objective,
+            &params,
+            &mut self.callback,
+        )?;
+        Ok(Schedule::new(table, room_matrix, live_info))
+    }
+
+    /// 全バンドを割り当てられない場合は、優先度の高いバンドからなるべく多く割り当てます
+    ///
+    /// 割り当てが改善されるたびに一部のバンドだけの結果も通知します。
+    pub fn assign_partial(&mut self, room_matrix: &RoomMatrix, live_info: &LiveInfo) -> Schedule {
+        let params = self.params(true);
+        Self::assign_partial_impl(
+            room_matrix,
+            live_info,
+            &self.objective,
+            &self.constraints,
+            &params,
+            &mut self.callback,
+        )
+    }
+}
+
+impl<T> LocalSearchScheduler<T> {
+    fn new_impl(callback: T) -> Self {
+        Self {
+            callback,
+            objective: MultiObjective::new().push(UserCoherencyObjective, 1.0),
+            constraints: ConstraintChain::default(),
+            time_limit: Duration::from_secs(10),
+            iteration_count: 100_000,
+            seed: 0,
+        }
+    }
+
+    /// 最大化する評価値を指定します。既定では部屋移動の少なさだけを評価します
+    pub fn with_objective(mut self, objective: MultiObjective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// 制約を追加します。バンドのスケジュールとメンバーの衝突は常に確認します
+    ///
+    /// 制約を満たさない割り当てや入れ替えは採用しません。
+    pub fn push_constraint<C: IConstraint + Send + Sync + 'static>(
+        mut self,
+        constraint: C,
+    ) -> Self {
+        self.constraints.push(Arc::new(constraint));
+        self
+    }
+
+    fn params(&self, allow_partial: bool) -> LocalSearchParams {
+        LocalSearchParams {
+            time_limit: self.time_limit,
+            iteration_count: self.iteration_count,
+            seed: self.seed,
+            allow_partial,
+        }
+    }
+
+    fn assign_partial_impl<U: IScheduleCallback>(
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+        objective: &MultiObjective,
+        constraints: &ConstraintChain,
+        params: &LocalSearchParams,
+        callback: &mut U,
+    ) -> Schedule {
+        // 初期解を必ず評価するので結果がないことはない
+        let table = LocalSearchImpl::new(room_matrix, live_info, constraints)
+            .run(objective, params, callback)
+            .unwrap();
+        Schedule::new(table, room_matrix, live_info)
+    }
+
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    pub fn with_iteration_count(mut self, iteration_count: usize) -> Self {
+        self.iteration_count = iteration_count;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Default for LocalSearchScheduler<()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct EmptyCallback;
+
+impl IScheduleCallback for EmptyCallback {
+    fn on_started(&mut self, _scheduler_info: &SchedulerInfo) {}
+
+    fn on_progress(&mut self, _task_id: TaskId, _task_info: &TaskInfo) {}
+
+    fn on_assigned(
+        &mut self,
+        _schedule: &Schedule,
+        _room_matrix: &RoomMatrix,
+        _live_info: &LiveInfo,
+    ) {
+    }
+
+    fn on_completed(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::algorithm::{
+        create_live_info, PairConstraint, PairRelation, RoomMatrix, SchedulerError,
+    };
+
+    use super::LocalSearchScheduler;
+
+    #[test]
+    fn simple() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["a".to_string()]),
+            ("band_c".to_string(), vec!["b".to_string()]),
+        ]);
+        let band_schedule = HashMap::from([
+            ("band_a".to_string(), vec![true, true]),
+            ("band_b".to_string(), vec![true, true]),
+            ("band_c".to_string(), vec![true, false]),
+        ]);
+        let room_matrix = RoomMatrix::builder().push_room(2).push_room(1).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let result = LocalSearchScheduler::new()
+            .with_iteration_count(1000)
+            .assign(&room_matrix, &live_info)
+            .unwrap();
+        assert_eq!(result.table().len(), 3);
+
+        // 同じ時間帯にメンバーが衝突していない
+        for span_id in room_matrix.spans() {
+            let mut members = HashSet::new();
+            for block_id in room_matrix.iter_span_blocks(*span_id) {
+                let Some(band_id) = result.table().get(block_id) else {
+                    continue;
+                };
+                for member_id in live_info.band_member_ids(*band_id).unwrap() {
+                    assert!(members.insert(*member_id));
+                }
+            }
+        }
+    }
+
+    // 部屋移動が少なくなるように同じ部屋に連続して割り当てられる
+    #[test]
+    fn coherency() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string(), "b".to_string()]),
+            ("band_b".to_string(), vec!["a".to_string(), "c".to_string()]),
+            ("band_c".to_string(), vec!["d".to_string()]),
+            ("band_d".to_string(), vec!["e".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 2]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(2).push_room(2).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let result = LocalSearchScheduler::new()
+            .with_iteration_count(1000)
+            .assign(&room_matrix, &live_info)
+            .unwrap();
+
+        let band_a = live_info.band_ids()[0];
+        let band_b = live_info.band_ids()[1];
+        let is_same_room = room_matrix.rooms().iter().any(|room_id| {
+            let band_ids: Vec<_> = room_matrix
+                .iter_room_blocks(*room_id)
+                .filter_map(|block_id| result.table().get(block_id))
+                .collect();
+            band_ids.contains(&&band_a) && band_ids.contains(&&band_b)
+        });
+        assert!(is_same_room);
+    }
+
+    // そもそも部屋数が足りない場合
+    #[test]
+    fn exhaustion() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 1]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(1).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let result = LocalSearchScheduler::new().assign(&room_matrix, &live_info);
+        assert!(matches!(
+            result,
+            Err(SchedulerError::NotEnoughBlocks {
+                block_count: 1,
+                band_count: 2
+            })
+        ));
+    }
+
+    // 部屋数が足りない場合は優先度の高いバンドを割り当てる
+    #[test]
+    fn partial() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+            ("band_c".to_string(), vec!["c".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 2]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(2).build();
+        let mut live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let band_a = live_info.band_ids()[0];
+        let band_b = live_info.band_ids()[1];
+        let band_c = live_info.band_ids()[2];
+        live_info.set_band_priority(band_a, 0);
+        live_info.set_band_priority(band_c, 2);
+
+        let result = LocalSearchScheduler::new()
+            .with_iteration_count(1000)
+            .assign_partial(&room_matrix, &live_info);
+        assert_eq!(result.table().len(), 2);
+        assert_eq!(result.unassigned_band_ids().len(), 1);
+        assert!(result.unassigned_band_ids()[0] == band_a);
+        assert!(result.table().values().any(|id| *id == band_b));
+        assert!(result.table().values().any(|id| *id == band_c));
+    }
+
+    // スケジュールが合わないバンドだけが外れる
+    #[test]
+    fn partial_schedule_mismatch() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+        ]);
+        let band_schedule = HashMap::from([
+            ("band_a".to_string(), vec![true, true]),
+            ("band_b".to_string(), vec![false, false]),
+        ]);
+        let room_matrix = RoomMatrix::builder().push_room(2).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let scheduler = LocalSearchScheduler::new().with_iteration_count(100);
+        assert!(matches!(
+            scheduler.assign(&room_matrix, &live_info),
+            Err(SchedulerError::NoFeasibleSchedule)
+        ));
+
+        let result = scheduler.assign_partial(&room_matrix, &live_info);
+        assert_eq!(result.table().len(), 1);
+        assert!(result.unassigned_band_ids()[0] == live_info.band_ids()[1]);
+    }
+
+    // 登録した制約を満たさない割り当ては採用しない
+    #[test]
+    fn constraint() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 2]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(2).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let band_a = live_info.band_ids()[0];
+        let band_b = live_info.band_ids()[1];
+        for (relation, first_band_id) in [
+            (PairRelation::Before, band_b),
+            (PairRelation::After, band_a),
+        ] {
+            let constraint =
+                PairConstraint::new(&room_matrix, &live_info).push(band_b, band_a, relation);
+            let result = LocalSearchScheduler::new()
+                .with_iteration_count(100)
+                .push_constraint(constraint)
+                .assign(&room_matrix, &live_info)
+                .unwrap();
+            assert_eq!(result.table().len(), 2);
+            assert!(result.table()[&room_matrix.blocks()[0]] == first_band_id);
+        }
+    }
+}
@@ -1,17 +1,50 @@
+mod concert;
+mod constraint;
 mod definition;
 mod detail;
+mod duty;
+mod error;
 mod evaluator;
+mod history;
 mod html_parser;
+mod local_search_scheduler;
+mod objective;
+mod rehearsal;
+mod running_order;
+mod schedule;
 mod scheduler;
+mod time;
+mod validation;
 
 use std::collections::{HashMap, HashSet};
 
-pub use definition::{RoomMatrix, Schedule, TraverseOperation};
-pub use evaluator::Evaluator;
+pub use concert::{ConcertSetting, ConcertSlot, ConcertTimetable};
+pub use constraint::{
+    BandScheduleConstraint, IConstraint, MemberConflictConstraint, MemberIdleConstraint,
+    PairConstraint, PairRelation,
+};
+pub use definition::{RoomMatrix, TraverseOperation};
+pub use duty::{DutyAssignment, DutyRoster, DutyRosterPlanner};
+pub use error::{LiveInfoError, SchedulerError};
+pub use evaluator::{Evaluator, IdleTime};
+pub use history::AssignHistory;
 pub use html_parser::HtmlParser;
-pub use scheduler::{IScheduleCallback, Scheduler, SchedulerInfo, TaskId, TaskInfo};
-
-use crate::{BandId, BlockId, UserId};
+pub use local_search_scheduler::LocalSearchScheduler;
+pub use objective::{
+    IObjective, MaxIdleTimeObjective, MultiObjective, ParetoFront, RoomDensityObjective,
+    ScheduleRanking, ScoredSchedule, SessionRotationObjective, SlotFairnessObjective,
+    TotalIdleTimeObjective, UserCoherencyObjective,
+};
+pub use rehearsal::{RehearsalSetting, RehearsalSlot, RehearsalTimetable};
+pub use running_order::{RunningOrder, RunningOrderPlanner};
+pub use schedule::{Schedule, ScheduleEntry};
+pub use scheduler::{
+    IScheduleCallback, Scheduler, SchedulerBuilder, SchedulerInfo, TaskId, TaskInfo,
+};
+pub use time::{LocalDateTime, SpanTime};
+pub use validation::{validate_live_info, LiveInfoWarning, ValidationReport};
+
+use crate::{Band, BandId, BlockId, InstrumentType, UserId};
 
 pub trait IParallelTreeCallback {
     fn notify(&mut self, indicies: &[u8]);
@@ -32,6 +65,12 @@ pub struct LiveInfo {
     band_member_table: HashMap<BandId, Vec<UserId>>,
     band_schedule_table: HashMap<BandId, Vec<bool>>,
 
+    /// バンドの優先度。未設定のバンドは 1 として扱う
+    band_priority_table: HashMap<BandId, u32>,
+
+    /// バンドの編成で使う楽器
+    band_instrument_table: HashMap<BandId, InstrumentType>,
+
     /// 部屋に割り当て可能なバンドのテーブル
     block_available_band_table: HashMap<BlockId, HashSet<BandId>>,
 }
@@ -53,8 +92,8 @@ impl LiveInfo {
         &self.band_ids
     }
 
-    pub fn band_name(&self, id: BandId) -> &str {
-        self.band_name_table.get(&id).unwrap()
+    pub fn band_name(&self, id: BandId) -> Option<&str> {
+        self.band_name_table.get(&id).map(|name| name.as_str())
     }
 
     pub fn band_hash(&self, id: BandId) -> Option<u64> {
@@ -85,6 +124,43 @@ impl LiveInfo {
         Some(*is_available)
     }
 
+    /// 全バンドを割り当てられないときに優先して割り当てる度合い
+    ///
+    /// 0 は割り当てなくても構わない任意参加のバンドを表します。
+    pub fn band_priority(&self, id: BandId) -> u32 {
+        *self.band_priority_table.get(&id).unwrap_or(&1)
+    }
+
+    pub fn set_band_priority(&mut self, id: BandId, priority: u32) {
+        self.band_priority_table.insert(id, priority);
+    }
+
+    /// バンドの編成で使う楽器。未設定のバンドは空
+    pub fn band_instruments(&self, id: BandId) -> InstrumentType {
+        self.band_instrument_table
+            .get(&id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_band_instruments(&mut self, id: BandId, instruments: InstrumentType) {
+        self.band_instrument_table.insert(id, instruments);
+    }
+
+    /// Band のメンバーの担当楽器から、同じ名前のバンドの編成を設定します
+    pub fn set_band_lineups(&mut self, bands: &[Band]) {
+        for band in bands {
+            let Some(id) = self
+                .band_ids
+                .iter()
+                .find(|id| self.band_name_table.get(id) == Some(&band.name))
+            else {
+                continue;
+            };
+            self.band_instrument_table.insert(*id, band.instruments());
+        }
+    }
+
     /// 指定の枠にバンドが参加可能かを取得します
     pub fn confirm_assignable(&self, block_id: BlockId, band_id: BandId) -> bool {
         let Some(set) = self.block_available_band_table.get(&block_id) else {
@@ -93,6 +169,70 @@ impl LiveInfo {
 
         set.contains(&band_id)
     }
+
+    /// 指定したバンドだけを含み、残りの枠を空き枠用のバンドで埋めた LiveInfo を生成します
+    ///
+    /// 空き枠用のバンドはメンバーがおらず、すべての枠に参加できます。空き枠用のバンドの識別子も返します。
+    pub(crate) fn with_bands(
+        &self,
+        band_ids: &[BandId],
+        free_block_count: usize,
+        room_matrix: &RoomMatrix,
+    ) -> (LiveInfo, Vec<BandId>) {
+        let free_band_ids: Vec<BandId> = (0..free_block_count).map(|_| BandId::new()).collect();
+        let span_count = room_matrix.spans().len();
+
+        let mut live_info = LiveInfo {
+            user_ids: self.user_ids.clone(),
+            user_identifier_table: self.user_identifier_table.clone(),
+            band_ids: band_ids.to_vec(),
+            band_name_table: filter_band_table(&self.band_name_table, band_ids),
+            band_hash_table: filter_band_table(&self.band_hash_table, band_ids),
+            band_member_table: filter_band_table(&self.band_member_table, band_ids),
+            band_schedule_table: filter_band_table(&self.band_schedule_table, band_ids),
+            band_priority_table: filter_band_table(&self.band_priority_table, band_ids),
+            band_instrument_table: filter_band_table(&self.band_instrument_table, band_ids),
+            block_available_band_table: HashMap::default(),
+        };
+        for free_band_id in &free_band_ids {
+            live_info.band_ids.push(*free_band_id);
+            live_info
+                .band_name_table
+                .insert(*free_band_id, String::default());
+            live_info.band_hash_table.insert(*free_band_id, 0);
+            live_info
+                .band_member_table
+                .insert(*free_band_id, Vec::default());
+            live_info
+                .band_schedule_table
+                .insert(*free_band_id, vec![true; span_count]);
+        }
+        for (block_id, available_band_ids) in &self.block_available_band_table {
+            let available_band_ids = available_band_ids
+                .iter()
+                .filter(|id| band_ids.contains(id))
+                .chain(&free_band_ids)
+                .copied()
+                .collect();
+            live_info
+                .block_available_band_table
+                .insert(*block_id, available_band_ids);
+        }
+
+        (live_info, free_band_ids)
+    }
+}
+
+// 指定したバンドの分だけ取り出す
+fn filter_band_table<T: Clone>(
+    table: &HashMap<BandId, T>,
+    band_ids: &[BandId],
+) -> HashMap<BandId, T> {
+    table
+        .iter()
+        .filter(|(id, _)| band_ids.contains(id))
+        .map(|(id, value)| (*id, value.clone()))
+        .collect()
 }
 
 // band_table: バンド名 → メンバーたち
@@ -100,7 +240,14 @@ pub fn create_live_info(
     band_table: &HashMap<String, Vec<String>>,
     band_schedule_table: &HashMap<String, Vec<bool>>,
     room_matrix: &RoomMatrix,
-) -> LiveInfo {
+) -> Result<LiveInfo, LiveInfoError> {
+    // 入力に問題があれば最初のひとつを返す
+    // まとめて確認したい場合は validate_live_info を使う
+    let report = validate_live_info(band_table, band_schedule_table, room_matrix);
+    if let Some(error) = report.errors.into_iter().next() {
+        return Err(error);
+    }
+
     // 重複と取り除いてユーザー一覧を生成
     let users: Vec<String> = {
         // 検索用のセット
@@ -157,14 +304,18 @@ pub fn create_live_info(
     // バンド ID -> メンバー ID
     let band_member_table: HashMap<BandId, Vec<UserId>> = band_ids
         .iter()
-        .enumerate()
-        .map(|(index, band_id)| {
-            let band_name = &bands[index];
-            let names = band_table.get(band_name).unwrap();
-            let member_ids: Vec<UserId> = names
-                .iter()
-                .map(|member| *user_identifier_reverse_table.get(member).unwrap())
-                .collect();
+        .zip(&bands)
+        .map(|(band_id, band_name)| {
+            // 重複して登録されたメンバーは一度だけ数える
+            let mut member_ids: Vec<UserId> = Vec::default();
+            for member in &band_table[band_name] {
+                let Some(member_id) = user_identifier_reverse_table.get(member) else {
+                    continue;
+                };
+                if !member_ids.contains(member_id) {
+                    member_ids.push(*member_id);
+                }
+            }
             (*band_id, member_ids)
         })
         .collect();
@@ -183,36 +334,24 @@ pub fn create_live_info(
 
         // バンドに所属しているメンバーのビット和を算出
         // これをバンドのハッシュ値とする
-        band_ids
+        band_member_table
             .iter()
-            .map(|id| {
-                let Some(member_ids) = band_member_table.get(id) else {
-                    panic!();
-                };
-
-                let mut hash = 0;
-                for member in member_ids {
-                    let index = member_hash_table.get(member).unwrap();
-                    hash |= index;
-                }
-
+            .map(|(id, member_ids)| {
+                let hash = member_ids
+                    .iter()
+                    .filter_map(|member| member_hash_table.get(member))
+                    .fold(0, |hash, bit| hash | bit);
                 (*id, hash)
             })
             .collect()
     };
 
     // バンドが参加できる時間帯の情報
-    let band_schedule_table: HashMap<BandId, Vec<bool>> = band_schedule_table
+    // スケジュール表のバンドはすべてバンド一覧にあることを確認済み
+    let band_schedule_table: HashMap<BandId, Vec<bool>> = band_ids
         .iter()
-        .map(|(str, schedule)| {
-            let (index, _str) = bands
-                .iter()
-                .enumerate()
-                .find(|(_index, band_name)| *band_name == str)
-                .unwrap();
-            let band_id = band_ids[index];
-            (band_id, schedule.clone())
-        })
+        .zip(&bands)
+        .map(|(band_id, band_name)| (*band_id, band_schedule_table[band_name].clone()))
         .collect();
 
     let mut block_available_band_table = HashMap::default();
@@ -237,7 +376,7 @@ pub fn create_live_info(
         }
     }
 
-    LiveInfo {
+    Ok(LiveInfo {
         user_ids,
         user_identifier_table,
         band_ids,
@@ -245,17 +384,51 @@ pub fn create_live_info(
         band_hash_table,
         band_member_table,
         band_schedule_table,
+        band_priority_table: HashMap::default(),
+        band_instrument_table: HashMap::default(),
         block_available_band_table,
-    }
+    })
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use std::collections::HashMap;
 
-    use crate::algorithm::RoomMatrix;
+    use crate::algorithm::{LiveInfoError, RoomMatrix};
+    use crate::LiveProgram;
+
+    use super::{create_live_info, LiveInfo};
 
-    use super::create_live_info;
+    // 部屋ひとつ、時間帯ひとつで全バンドが出演できるライブ
+    pub fn create_live(band_table: &[(&str, &[&str])]) -> LiveInfo {
+        let band_table: HashMap<String, Vec<String>> = band_table
+            .iter()
+            .map(|(name, members)| {
+                (
+                    name.to_string(),
+                    members.iter().map(|x| x.to_string()).collect(),
+                )
+            })
+            .collect();
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(1).build();
+        create_live_info(&band_table, &band_schedule, &room_matrix).unwrap()
+    }
+
+    // band_a, band_b, band_c の順に出演するプログラム
+    pub fn create_program() -> LiveProgram {
+        LiveProgram {
+            user_names: Vec::default(),
+            item: vec![
+                "band_a".to_string(),
+                "band_b".to_string(),
+                "band_c".to_string(),
+            ],
+        }
+    }
 
     #[test]
     fn simple() {
@@ -271,7 +444,7 @@ mod tests {
             .map(|key| (key.to_string(), vec![true; 16]))
             .collect();
         let room_matrix = RoomMatrix::builder().push_room(1).build();
-        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix);
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
 
         let band_id_a = live_info.band_ids()[0];
         let band_id_b = live_info.band_ids()[1];
@@ -288,4 +461,70 @@ mod tests {
         assert!(members.contains(&"shikama_shuto"));
         assert!(members.contains(&"zzz"));
     }
+
+    #[test]
+    fn invalid_input() {
+        let room_matrix = RoomMatrix::builder().push_room(2).build();
+        let band_table = HashMap::from([("a_band".to_string(), vec!["a".to_string()])]);
+
+        // スケジュールがない
+        let result = create_live_info(&band_table, &HashMap::default(), &room_matrix);
+        assert_eq!(
+            result.err(),
+            Some(LiveInfoError::MissingSchedule {
+                band_name: "a_band".to_string()
+            })
+        );
+
+        // スケジュールが時間帯の数より短い
+        let band_schedule = HashMap::from([("a_band".to_string(), vec![true])]);
+        let result = create_live_info(&band_table, &band_schedule, &room_matrix);
+        assert_eq!(
+            result.err(),
+            Some(LiveInfoError::ScheduleTooShort {
+                band_name: "a_band".to_string(),
+                length: 1,
+                span_count: 2
+            })
+        );
+
+        // バンド一覧にないバンドのスケジュール
+        let band_schedule = HashMap::from([
+            ("a_band".to_string(), vec![true; 2]),
+            ("b_band".to_string(), vec![true; 2]),
+        ]);
+        let result = create_live_info(&band_table, &band_schedule, &room_matrix);
+        assert_eq!(
+            result.err(),
+            Some(LiveInfoError::UnknownBand {
+                band_name: "b_band".to_string()
+            })
+        );
+
+        // メンバーがいない
+        let band_table = HashMap::from([("a_band".to_string(), Vec::default())]);
+        let band_schedule = HashMap::from([("a_band".to_string(), vec![true; 2])]);
+        let result = create_live_info(&band_table, &band_schedule, &room_matrix);
+        assert_eq!(
+            result.err(),
+            Some(LiveInfoError::MissingMember {
+                band_name: "a_band".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn too_many_users() {
+        let room_matrix = RoomMatrix::builder().push_room(1).build();
+        let band_table = HashMap::from([(
+            "a_band".to_string(),
+            (0..65).map(|index| format!("user_{index}")).collect(),
+        )]);
+        let band_schedule = HashMap::from([("a_band".to_string(), vec![true])]);
+        let result = create_live_info(&band_table, &band_schedule, &room_matrix);
+        assert_eq!(
+            result.err(),
+            Some(LiveInfoError::TooManyUsers { user_count: 65 })
+        );
+    }
 }
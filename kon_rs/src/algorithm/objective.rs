@@ -0,0 +1,731 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::{BandId, BlockId, RoomId, UserId};
+
+use super::{
+    AssignHistory, Evaluator, IScheduleCallback, LiveInfo, RoomMatrix, Schedule, SchedulerInfo,
+    TaskId, TaskInfo,
+};
+
+/// スケジュールの評価軸
+///
+/// 値が大きいほど優れたスケジュールとして扱います。
+pub trait IObjective {
+    fn name(&self) -> &str;
+
+    fn evaluate(
+        &self,
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> f64;
+}
+
+/// 部屋移動の手間の少なさ
+#[derive(Default, Clone)]
+pub struct UserCoherencyObjective;
+
+impl IObjective for UserCoherencyObjective {
+    fn name(&self) -> &str {
+        "user_coherency"
+    }
+
+    fn evaluate(
+        &self,
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> f64 {
+        Evaluator::evaluate(table, room_matrix, live_info) as f64
+    }
+}
+
+/// 部屋をどれくらい使い切れているか
+///
+/// 空き枠が部屋の最後にまとまっているほど高得点にします。
+#[derive(Default, Clone)]
+pub struct RoomDensityObjective;
+
+impl IObjective for RoomDensityObjective {
+    fn name(&self) -> &str {
+        "room_density"
+    }
+
+    fn evaluate(
+        &self,
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        _live_info: &LiveInfo,
+    ) -> f64 {
+        Evaluator::evaluate_room_density(table, room_matrix) as f64
+    }
+}
+
+/// 朝一番と最後の枠の負担がメンバー間で偏っていないか
+///
+/// メンバーごとに端の時間帯に入った回数を数え、二乗和が小さいほど高得点にします。
+#[derive(Default, Clone)]
+pub struct SlotFairnessObjective;
+
+impl IObjective for SlotFairnessObjective {
+    fn name(&self) -> &str {
+        "slot_fairness"
+    }
+
+    fn evaluate(
+        &self,
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> f64 {
+        let spans = room_matrix.spans();
+        let (Some(first), Some(last)) = (spans.first(), spans.last()) else {
+            return 0.0;
+        };
+
+        // 時間帯がひとつなら first と last が同じなので重複して数えないようにする
+        let mut edge_spans = vec![*first];
+        if first != last {
+            edge_spans.push(*last);
+        }
+
+        let mut count_table: HashMap<UserId, u32> = HashMap::default();
+        for span_id in edge_spans {
+            for block_id in room_matrix.iter_span_blocks(span_id) {
+                let Some(band_id) = table.get(block_id) else {
+                    continue;
+                };
+                let Some(member_ids) = live_info.band_member_ids(*band_id) else {
+                    continue;
+                };
+                for member_id in member_ids {
+                    *count_table.entry(*member_id).or_default() += 1;
+                }
+            }
+        }
+
+        let sum: u32 = count_table.values().map(|count| count * count).sum();
+        -(sum as f64)
+    }
+}
+
+/// メンバーが出番の間に待つ時間帯の合計の少なさ
+#[derive(Default, Clone)]
+pub struct TotalIdleTimeObjective;
+
+impl IObjective for TotalIdleTimeObjective {
+    fn name(&self) -> &str {
+        "total_idle_time"
+    }
+
+    fn evaluate(
+        &self,
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> f64 {
+        let total: u32 = Evaluator::evaluate_member_idle_time(table, room_matrix, live_info)
+            .values()
+            .map(|idle_time| idle_time.total)
+            .sum();
+        -(total as f64)
+    }
+}
+
+/// 最も長く待つメンバーの待ち時間の短さ
+#[derive(Default, Clone)]
+pub struct MaxIdleTimeObjective;
+
+impl IObjective for MaxIdleTimeObjective {
+    fn name(&self) -> &str {
+        "max_idle_time"
+    }
+
+    fn evaluate(
+        &self,
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> f64 {
+        let max = Evaluator::evaluate_member_idle_time(table, room_matrix, live_info)
+            .values()
+            .map(|idle_time| idle_time.max)
+            .max()
+            .unwrap_or_default();
+        -(max as f64)
+    }
+}
+
+/// 過去の練習会と同じ時間帯・部屋に入らないか
+///
+/// 同じバンドが毎回朝一番の枠に入るといった偏りを、過去に入った回数をペナルティにして避けます。
+#[derive(Default, Clone)]
+pub struct SessionRotationObjective {
+    history: AssignHistory,
+}
+
+impl SessionRotationObjective {
+    pub fn new(history: AssignHistory) -> Self {
+        Self { history }
+    }
+}
+
+impl IObjective for SessionRotationObjective {
+    fn name(&self) -> &str {
+        "session_rotation"
+    }
+
+    fn evaluate(
+        &self,
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> f64 {
+        let penalty: usize = table
+            .iter()
+            .filter_map(|(block_id, band_id)| {
+                let (span_index, room_index) = room_matrix.block_location(*block_id)?;
+                let band_name = live_info.band_name(*band_id)?;
+                Some(
+                    self.history.span_count(band_name, span_index)
+                        + self.history.room_count(band_name, room_index),
+                )
+            })
+            .sum();
+        -(penalty as f64)
+    }
+}
+
+/// 複数の評価軸を重み付きでまとめたもの
+#[derive(Default)]
+pub struct MultiObjective {
+    objectives: Vec<(Box<dyn IObjective + Send + Sync>, f64)>,
+}
+
+impl MultiObjective {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push<T>(mut self, objective: T, weight: f64) -> Self
+    where
+        T: IObjective + Send + Sync + 'static,
+    {
+        self.objectives.push((Box::new(objective), weight));
+        self
+    }
+
+    /// 評価軸の名前
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.objectives
+            .iter()
+            .map(|(objective, _)| objective.name())
+    }
+
+    /// 評価軸ごとの評価値
+    pub fn evaluate(
+        &self,
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> Vec<f64> {
+        self.objectives
+            .iter()
+            .map(|(objective, _)| objective.evaluate(table, room_matrix, live_info))
+            .collect()
+    }
+
+    /// 評価値の重み付き和
+    pub fn weighted_sum(&self, scores: &[f64]) -> f64 {
+        self.objectives
+            .iter()
+            .zip(scores)
+            .map(|((_, weight), score)| weight * score)
+            .sum()
+    }
+
+    /// スケジュールを評価して、評価軸ごとの評価値と重み付き和をまとめます
+    pub fn score(
+        &self,
+        table: &HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> ScoredSchedule {
+        let scores = self.evaluate(table, room_matrix, live_info);
+        self.create_scored_schedule(
+            Schedule::new(table.clone(), room_matrix, live_info),
+            &scores,
+        )
+    }
+
+    fn create_scored_schedule(&self, schedule: Schedule, scores: &[f64]) -> ScoredSchedule {
+        ScoredSchedule {
+            schedule,
+            scores: self
+                .names()
+                .map(|name| name.to_string())
+                .zip(scores.iter().copied())
+                .collect(),
+            weighted_score: self.weighted_sum(scores),
+        }
+    }
+}
+
+/// 評価値つきのスケジュール
+#[derive(Clone)]
+pub struct ScoredSchedule {
+    pub schedule: Schedule,
+
+    /// 評価軸の名前と評価値
+    pub scores: Vec<(String, f64)>,
+
+    /// 評価値の重み付き和
+    pub weighted_score: f64,
+}
+
+impl ScoredSchedule {
+    /// 名前を指定して評価値を取得します
+    pub fn score(&self, name: &str) -> Option<f64> {
+        self.scores
+            .iter()
+            .find(|(objective_name, _)| objective_name == name)
+            .map(|(_, score)| *score)
+    }
+}
+
+/// パレート最適なスケジュールの集合
+///
+/// IScheduleCallback として Scheduler に渡すと、見つかったスケジュールを逐次ふるいにかけます。
+pub struct ParetoFront {
+    objective: MultiObjective,
+    schedules: Vec<(Schedule, Vec<f64>)>,
+}
+
+impl ParetoFront {
+    pub fn new(objective: MultiObjective) -> Self {
+        Self {
+            objective,
+            schedules: Vec::default(),
+        }
+    }
+
+    /// スケジュールを評価して、他のスケジュールに支配されていなければ追加します
+    pub fn insert(
+        &mut self,
+        schedule: &Schedule,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> bool {
+        let scores = self
+            .objective
+            .evaluate(schedule.table(), room_matrix, live_info);
+
+        // 評価値がまったく同じものも新しい選択肢にはならないので追加しない
+        if self
+            .schedules
+            .iter()
+            .any(|(_, other)| is_dominated_or_equal(&scores, other))
+        {
+            return false;
+        }
+
+        self.schedules
+            .retain(|(_, other)| !is_dominated_or_equal(other, &scores));
+        self.schedules.push((schedule.clone(), scores));
+        true
+    }
+
+    pub fn schedules(&self) -> Vec<ScoredSchedule> {
+        self.schedules
+            .iter()
+            .map(|(schedule, scores)| {
+                self.objective
+                    .create_scored_schedule(schedule.clone(), scores)
+            })
+            .collect()
+    }
+}
+
+impl IScheduleCallback for Arc<Mutex<ParetoFront>> {
+    fn on_started(&mut self, _scheduler_info: &SchedulerInfo) {}
+
+    fn on_progress(&mut self, _task_id: TaskId, _task_info: &TaskInfo) {}
+
+    fn on_assigned(&mut self, schedule: &Schedule, room_matrix: &RoomMatrix, live_info: &LiveInfo) {
+        self.lock()
+            .unwrap()
+            .insert(schedule, room_matrix, live_info);
+    }
+
+    fn on_completed(&mut self) {}
+}
+
+/// 重み付き和の上位のスケジュール
+///
+/// IScheduleCallback として Scheduler に渡すと、見つかったスケジュールを逐次評価して上位だけを残します。
+pub struct ScheduleRanking {
+    objective: MultiObjective,
+
+    // 残すスケジュールの数
+    capacity: usize,
+
+    // 重み付き和の降順
+    schedules: Vec<ScoredSchedule>,
+}
+
+impl ScheduleRanking {
+    pub fn new(objective: MultiObjective, capacity: usize) -> Self {
+        Self {
+            objective,
+            capacity,
+            schedules: Vec::default(),
+        }
+    }
+
+    /// スケジュールを評価して、上位に入れば追加します
+    pub fn insert(
+        &mut self,
+        schedule: &Schedule,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> bool {
+        let scores = self
+            .objective
+            .evaluate(schedule.table(), room_matrix, live_info);
+        let weighted_score = self.objective.weighted_sum(&scores);
+
+        // 同点なら先に見つかったものを優先する
+        let position = self
+            .schedules
+            .partition_point(|x| weighted_score <= x.weighted_score);
+        if self.capacity <= position {
+            return false;
+        }
+
+        self.schedules.insert(
+            position,
+            self.objective
+                .create_scored_schedule(schedule.clone(), &scores),
+        );
+        self.schedules.truncate(self.capacity);
+        true
+    }
+
+    /// 重み付き和の降順に並んだスケジュール
+    pub fn schedules(&self) -> &[ScoredSchedule] {
+        &self.schedules
+    }
+}
+
+impl IScheduleCallback for Arc<Mutex<ScheduleRanking>> {
+    fn on_started(&mut self, _scheduler_info: &SchedulerInfo) {}
+
+    fn on_progress(&mut self, _task_id: TaskId, _task_info: &TaskInfo) {}
+
+    fn on_assigned(&mut self, schedule: &Schedule, room_matrix: &RoomMatrix, live_info: &LiveInfo) {
+        self.lock()
+            .unwrap()
+            .insert(schedule, room_matrix, live_info);
+    }
+
+    fn on_completed(&mut self) {}
+}
+
+// lhs が rhs に支配されている (すべての評価軸で rhs 以下) か
+fn is_dominated_or_equal(lhs: &[f64], rhs: &[f64]) -> bool {
+    lhs.iter().zip(rhs).all(|(lhs, rhs)| lhs <= rhs)
+}
+
+// 部屋ごとに枠の順でバンドを並べる
+// 空き枠は BandId::invalid() で埋める
+pub(crate) fn create_room_assign(
+    table: &HashMap<BlockId, BandId>,
+    room_matrix: &RoomMatrix,
+) -> HashMap<RoomId, Vec<BandId>> {
+    room_matrix
+        .rooms()
+        .iter()
+        .map(|room_id| {
+            let band_ids = room_matrix
+                .iter_room_blocks(*room_id)
+                .map(|block_id| *table.get(block_id).unwrap_or(&BandId::invalid()))
+                .collect();
+            (*room_id, band_ids)
+        })
+        .collect()
+}
+
+// 空き枠のハッシュ値は 0 とする
+pub(crate) fn create_band_hash_table(live_info: &LiveInfo) -> HashMap<BandId, u64> {
+    let mut band_hash_table: HashMap<BandId, u64> = live_info
+        .band_ids()
+        .iter()
+        .map(|id| (*id, live_info.band_hash(*id).unwrap()))
+        .collect();
+    band_hash_table.insert(BandId::invalid(), 0);
+    band_hash_table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::algorithm::{create_live_info, AssignHistory, RoomMatrix, Scheduler};
+
+    use super::{
+        IObjective, MaxIdleTimeObjective, MultiObjective, ParetoFront, RoomDensityObjective,
+        ScheduleRanking, SessionRotationObjective, SlotFairnessObjective, TotalIdleTimeObjective,
+        UserCoherencyObjective,
+    };
+
+    #[test]
+    fn weighted_sum() {
+        let objective = MultiObjective::new()
+            .push(UserCoherencyObjective, 2.0)
+            .push(SlotFairnessObjective, 0.5);
+        assert_eq!(objective.weighted_sum(&[3.0, -4.0]), 4.0);
+    }
+
+    // 同じメンバーが端の時間帯に偏るほど評価が下がる
+    #[test]
+    fn slot_fairness() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["a".to_string()]),
+            ("band_c".to_string(), vec!["b".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 3]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(3).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let band_ids = live_info.band_ids();
+        let blocks: Vec<_> = room_matrix
+            .spans()
+            .iter()
+            .map(|span_id| *room_matrix.iter_span_blocks(*span_id).next().unwrap())
+            .collect();
+
+        // a が朝と夜の両方に入る
+        let bad_table = HashMap::from([
+            (blocks[0], band_ids[0]),
+            (blocks[1], band_ids[2]),
+            (blocks[2], band_ids[1]),
+        ]);
+        // a と b で端の枠を分け合う
+        let good_table = HashMap::from([
+            (blocks[0], band_ids[0]),
+            (blocks[1], band_ids[1]),
+            (blocks[2], band_ids[2]),
+        ]);
+
+        let objective = SlotFairnessObjective;
+        let bad_score = objective.evaluate(&bad_table, &room_matrix, &live_info);
+        let good_score = objective.evaluate(&good_table, &room_matrix, &live_info);
+        assert!(bad_score < good_score);
+    }
+
+    // 出番の間が空くほど評価が下がる
+    #[test]
+    fn idle_time() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["a".to_string()]),
+            ("band_c".to_string(), vec!["b".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 3]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(3).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let blocks: Vec<_> = room_matrix
+            .iter_room_blocks(room_matrix.rooms()[0])
+            .copied()
+            .collect();
+        let band_ids = live_info.band_ids();
+
+        // a が朝と夜に入って 1 時間帯待つ
+        let bad_table = HashMap::from([
+            (blocks[0], band_ids[0]),
+            (blocks[1], band_ids[2]),
+            (blocks[2], band_ids[1]),
+        ]);
+        let good_table = HashMap::from([
+            (blocks[0], band_ids[0]),
+            (blocks[1], band_ids[1]),
+            (blocks[2], band_ids[2]),
+        ]);
+
+        for objective in [
+            &TotalIdleTimeObjective as &dyn IObjective,
+            &MaxIdleTimeObjective,
+        ] {
+            let bad_score = objective.evaluate(&bad_table, &room_matrix, &live_info);
+            let good_score = objective.evaluate(&good_table, &room_matrix, &live_info);
+            assert_eq!(bad_score, -1.0);
+            assert_eq!(good_score, 0.0);
+        }
+    }
+
+    // 過去に入った時間帯を避けるほど評価が上がる
+    #[test]
+    fn session_rotation() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 2]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(2).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let room_id = room_matrix.rooms()[0];
+        let blocks: Vec<_> = room_matrix.iter_room_blocks(room_id).copied().collect();
+        let band_ids = live_info.band_ids();
+
+        // band_a が 2 回続けて朝一番
+        let same_table = HashMap::from([(blocks[0], band_ids[0]), (blocks[1], band_ids[1])]);
+        let swapped_table = HashMap::from([(blocks[0], band_ids[1]), (blocks[1], band_ids[0])]);
+        let mut history = AssignHistory::new();
+        history.push("第1回", &same_table, &room_matrix, &live_info);
+        history.push("第2回", &same_table, &room_matrix, &live_info);
+
+        let objective = SessionRotationObjective::new(history);
+        let same_score = objective.evaluate(&same_table, &room_matrix, &live_info);
+        let swapped_score = objective.evaluate(&swapped_table, &room_matrix, &live_info);
+        assert!(same_score < swapped_score);
+    }
+
+    #[test]
+    fn pareto_front() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string(), "b".to_string()]),
+            ("band_b".to_string(), vec!["a".to_string()]),
+            ("band_c".to_string(), vec!["c".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 3]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(3).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let objective = MultiObjective::new()
+            .push(UserCoherencyObjective, 1.0)
+            .push(SlotFairnessObjective, 1.0);
+        let schedules = Scheduler::new()
+            .assign_pareto(&room_matrix, &live_info, objective)
+            .unwrap();
+        assert!(!schedules.is_empty());
+
+        // パレート解同士はお互いに支配しない
+        for lhs in &schedules {
+            assert_eq!(lhs.scores.len(), 2);
+            for rhs in &schedules {
+                let is_dominated = lhs
+                    .scores
+                    .iter()
+                    .zip(&rhs.scores)
+                    .all(|((_, lhs), (_, rhs))| lhs <= rhs);
+                let is_equal = lhs
+                    .scores
+                    .iter()
+                    .zip(&rhs.scores)
+                    .all(|((_, lhs), (_, rhs))| lhs == rhs);
+                assert!(!is_dominated || is_equal);
+            }
+        }
+
+        // 全探索の結果のうち支配されているものは含まれない
+        let mut front = ParetoFront::new(MultiObjective::new().push(UserCoherencyObjective, 1.0));
+        for schedule in Scheduler::new().assign(&room_matrix, &live_info).unwrap() {
+            front.insert(&schedule, &room_matrix, &live_info);
+        }
+        assert_eq!(front.schedules().len(), 1);
+    }
+
+    #[test]
+    fn score() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["a".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 3]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(3).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        // 同じメンバーが連続して、空き枠が最後にある
+        let blocks: Vec<_> = room_matrix
+            .iter_room_blocks(room_matrix.rooms()[0])
+            .copied()
+            .collect();
+        let band_ids = live_info.band_ids();
+        let table = HashMap::from([(blocks[0], band_ids[0]), (blocks[1], band_ids[1])]);
+
+        let objective = MultiObjective::new()
+            .push(UserCoherencyObjective, 2.0)
+            .push(RoomDensityObjective, 1.0);
+        let scored_schedule = objective.score(&table, &room_matrix, &live_info);
+        assert_eq!(scored_schedule.score("user_coherency"), Some(1.0));
+        assert_eq!(scored_schedule.score("room_density"), Some(0.0));
+        assert_eq!(scored_schedule.score("unknown"), None);
+        assert_eq!(scored_schedule.weighted_score, 2.0);
+        assert!(scored_schedule.schedule.table() == &table);
+    }
+
+    #[test]
+    fn ranking() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string(), "b".to_string()]),
+            ("band_b".to_string(), vec!["a".to_string()]),
+            ("band_c".to_string(), vec!["c".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 3]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(3).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let create_objective = || MultiObjective::new().push(UserCoherencyObjective, 1.0);
+        let schedules = Scheduler::new()
+            .assign_ranked(&room_matrix, &live_info, create_objective(), 3)
+            .unwrap();
+        assert_eq!(schedules.len(), 3);
+
+        // 重み付き和の降順
+        assert!(schedules
+            .windows(2)
+            .all(|x| x[1].weighted_score <= x[0].weighted_score));
+
+        // 全探索の結果のうち最も良いものが先頭
+        let best_score = Scheduler::new()
+            .assign(&room_matrix, &live_info)
+            .unwrap()
+            .iter()
+            .map(|schedule| {
+                create_objective()
+                    .score(schedule.table(), &room_matrix, &live_info)
+                    .weighted_score
+            })
+            .fold(f64::MIN, f64::max);
+        assert_eq!(schedules[0].weighted_score, best_score);
+
+        // 上位に入らないものは追加されない
+        let mut ranking = ScheduleRanking::new(create_objective(), 1);
+        let schedule = &schedules[0].schedule;
+        assert!(ranking.insert(schedule, &room_matrix, &live_info));
+        assert!(!ranking.insert(schedule, &room_matrix, &live_info));
+        assert_eq!(ranking.schedules().len(), 1);
+    }
+}
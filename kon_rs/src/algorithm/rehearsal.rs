@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use crate::{Band, InstrumentType, LiveProgram};
+
+use super::{LiveInfo, LocalDateTime, RoomMatrix, Schedule, SchedulerError, SpanTime};
+
+/// ライブ当日のリハーサルの設定
+///
+/// リハーサルは出演と逆順に行い、開場前に終わるように時間を割り当てます。
+/// バンドは LiveProgram の出演バンド名で指定します。
+pub struct RehearsalSetting {
+    // 開場時刻
+    doors: LocalDateTime,
+
+    // 最後のリハーサルから開場までに空ける時間
+    end_margin_minutes: u32,
+
+    // リハーサルの間の入れ替え時間
+    interval_minutes: u32,
+
+    // 楽器によらないリハーサルの時間
+    base_minutes: u32,
+
+    // 楽器ごとに追加するリハーサルの時間
+    instrument_minutes_table: Vec<(InstrumentType, u32)>,
+
+    band_instrument_table: HashMap<String, InstrumentType>,
+
+    // 楽器によらず指定したリハーサルの時間
+    rehearsal_minutes_table: HashMap<String, u32>,
+
+    // リハーサルを行う部屋の名前
+    stage_name: String,
+}
+
+impl RehearsalSetting {
+    pub fn new(doors: LocalDateTime) -> Self {
+        Self {
+            doors,
+            end_margin_minutes: 0,
+            interval_minutes: 0,
+            base_minutes: 15,
+            instrument_minutes_table: vec![
+                (InstrumentType::DRUMS, 10),
+                (InstrumentType::KEYBOARD, 5),
+                (InstrumentType::PIANO, 5),
+            ],
+            band_instrument_table: HashMap::default(),
+            rehearsal_minutes_table: HashMap::default(),
+            stage_name: "ステージ".to_string(),
+        }
+    }
+
+    /// 最後のリハーサルから開場までに空ける時間
+    pub fn with_end_margin(mut self, minutes: u32) -> Self {
+        self.end_margin_minutes = minutes;
+        self
+    }
+
+    /// リハーサルの間の入れ替え時間
+    pub fn with_interval(mut self, minutes: u32) -> Self {
+        self.interval_minutes = minutes;
+        self
+    }
+
+    /// 楽器によらないリハーサルの時間
+    pub fn with_base_minutes(mut self, minutes: u32) -> Self {
+        self.base_minutes = minutes;
+        self
+    }
+
+    /// 楽器を使うバンドに追加するリハーサルの時間
+    ///
+    /// 複数の楽器を指定した場合は、そのうちひとつでも使うバンドに追加します。
+    pub fn with_instrument_minutes(mut self, instruments: InstrumentType, minutes: u32) -> Self {
+        self.instrument_minutes_table
+            .retain(|(other, _)| other.bits() != instruments.bits());
+        self.instrument_minutes_table.push((instruments, minutes));
+        self
+    }
+
+    /// バンドが使う楽器を指定します
+    pub fn with_band_instruments(mut self, band_name: &str, instruments: InstrumentType) -> Self {
+        self.band_instrument_table
+            .insert(band_name.to_string(), instruments);
+        self
+    }
+
+    /// メンバーの担当楽器からバンドが使う楽器を指定します
+    pub fn with_band_lineups(mut self, bands: &[Band]) -> Self {
+        for band in bands {
+            self.band_instrument_table
+                .insert(band.name.clone(), band.instruments());
+        }
+        self
+    }
+
+    /// 楽器によらずバンドのリハーサルの時間を指定します
+    pub fn with_rehearsal_minutes(mut self, band_name: &str, minutes: u32) -> Self {
+        self.rehearsal_minutes_table
+            .insert(band_name.to_string(), minutes);
+        self
+    }
+
+    /// 出力するときのリハーサルを行う部屋の名前
+    pub fn with_stage_name(mut self, stage_name: &str) -> Self {
+        self.stage_name = stage_name.to_string();
+        self
+    }
+
+    /// バンドのリハーサルの時間
+    pub fn rehearsal_minutes(&self, band_name: &str) -> u32 {
+        if let Some(minutes) = self.rehearsal_minutes_table.get(band_name) {
+            return *minutes;
+        }
+
+        let instruments = self
+            .band_instrument_table
+            .get(band_name)
+            .copied()
+            .unwrap_or_default();
+        self.base_minutes
+            + self
+                .instrument_minutes_table
+                .iter()
+                .filter(|(other, _)| instruments.intersects(*other))
+                .map(|(_, minutes)| minutes)
+                .sum::<u32>()
+    }
+}
+
+/// リハーサルひとつ分の時間
+#[derive(Debug, Clone, PartialEq)]
+pub struct RehearsalSlot {
+    pub band_name: String,
+
+    /// リハーサルの開始・終了時刻
+    pub time: SpanTime,
+}
+
+/// ライブ当日のリハーサルの時間割
+pub struct RehearsalTimetable {
+    slots: Vec<RehearsalSlot>,
+    stage_name: String,
+}
+
+impl RehearsalTimetable {
+    pub(crate) fn new(program: &LiveProgram, setting: &RehearsalSetting) -> Self {
+        // 開場時刻から遡って、最初に出演するバンドが最後にリハーサルする
+        let mut slots: Vec<RehearsalSlot> = Vec::default();
+        let mut current = setting
+            .doors
+            .add_minutes(-(setting.end_margin_minutes as i64));
+        for band_name in &program.item {
+            if !slots.is_empty() {
+                current = current.add_minutes(-(setting.interval_minutes as i64));
+            }
+
+            let start = current.add_minutes(-(setting.rehearsal_minutes(band_name) as i64));
+            slots.push(RehearsalSlot {
+                band_name: band_name.to_string(),
+                time: SpanTime::new(start, current),
+            });
+            current = start;
+        }
+        slots.reverse();
+
+        Self {
+            slots,
+            stage_name: setting.stage_name.clone(),
+        }
+    }
+
+    /// リハーサルを行う順に並んだ時間
+    pub fn slots(&self) -> &[RehearsalSlot] {
+        &self.slots
+    }
+
+    /// 最初のリハーサルの開始時刻。入り時間の目安になります
+    pub fn start(&self) -> Option<LocalDateTime> {
+        self.slots.first().map(|slot| slot.time.start)
+    }
+
+    /// 練習のスケジュールと同じ形式にします
+    ///
+    /// ステージひとつの部屋割りとして、ITimetableExporter や ICalendarExporter で出力できます。
+    /// live_info に含まれないバンドの枠は空き枠になります。
+    /// リハーサルが部屋ひとつに並べられる枠の数 (255) を超えると SchedulerError::TooManyBlocks で失敗します。
+    pub fn to_schedule(&self, live_info: &LiveInfo) -> Result<Schedule, SchedulerError> {
+        let Ok(block_count) = u8::try_from(self.slots.len()) else {
+            return Err(SchedulerError::TooManyBlocks {
+                block_count: self.slots.len(),
+            });
+        };
+
+        let mut builder = RoomMatrix::builder().push_named_room(&self.stage_name, block_count);
+        for slot in &self.slots {
+            builder = builder.push_span_time(slot.time);
+        }
+        let room_matrix = builder.build();
+
+        let block_ids = room_matrix.iter_room_blocks(room_matrix.rooms()[0]);
+        let table = block_ids
+            .zip(&self.slots)
+            .filter_map(|(block_id, slot)| {
+                let band_id = live_info
+                    .band_ids()
+                    .iter()
+                    .find(|id| live_info.band_name(**id) == Some(slot.band_name.as_str()))?;
+                Some((*block_id, *band_id))
+            })
+            .collect();
+        Ok(Schedule::new(table, &room_matrix, live_info))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithm::tests::{create_live, create_program};
+    use crate::algorithm::{LocalDateTime, SchedulerError};
+    use crate::export::{ITimetableExporter, MarkdownTimetableExporter};
+    use crate::{InstrumentType, LiveProgram};
+
+    use super::RehearsalSetting;
+
+    #[test]
+    fn rehearsal_minutes() {
+        let setting = RehearsalSetting::new(LocalDateTime::new(2024, 4, 6, 17, 30))
+            .with_band_instruments("band_a", InstrumentType::DRUMS | InstrumentType::KEYBOARD)
+            .with_band_instruments("band_b", InstrumentType::VOCAL)
+            .with_instrument_minutes(InstrumentType::KEYBOARD, 10)
+            .with_rehearsal_minutes("band_c", 30);
+        assert_eq!(setting.rehearsal_minutes("band_a"), 15 + 10 + 10);
+        assert_eq!(setting.rehearsal_minutes("band_b"), 15);
+        assert_eq!(setting.rehearsal_minutes("band_c"), 30);
+        assert_eq!(setting.rehearsal_minutes("unknown"), 15);
+    }
+
+    // 出演と逆順に、開場前に終わるように並べる
+    #[test]
+    fn reverse_order() {
+        let setting = RehearsalSetting::new(LocalDateTime::new(2024, 4, 6, 17, 30))
+            .with_end_margin(30)
+            .with_interval(5)
+            .with_band_instruments("band_a", InstrumentType::DRUMS);
+        let timetable = create_program().create_rehearsal_timetable(&setting);
+
+        let slots: Vec<(&str, String)> = timetable
+            .slots()
+            .iter()
+            .map(|slot| (slot.band_name.as_str(), slot.time.to_time_string()))
+            .collect();
+        assert_eq!(
+            slots,
+            vec![
+                ("band_c", "15:55-16:10".to_string()),
+                ("band_b", "16:15-16:30".to_string()),
+                ("band_a", "16:35-17:00".to_string()),
+            ]
+        );
+        assert_eq!(
+            timetable.start(),
+            Some(LocalDateTime::new(2024, 4, 6, 15, 55))
+        );
+    }
+
+    #[test]
+    fn export() {
+        let live_info = create_live(&[
+            ("band_a", &["band_a"]),
+            ("band_b", &["band_b"]),
+            ("band_c", &["band_c"]),
+        ]);
+
+        let setting = RehearsalSetting::new(LocalDateTime::new(2024, 4, 6, 17, 30));
+        let schedule = create_program()
+            .create_rehearsal_timetable(&setting)
+            .to_schedule(&live_info)
+            .unwrap();
+        assert_eq!(
+            MarkdownTimetableExporter.export(&schedule),
+            "| 時間 | ステージ |\n\
+             | --- | --- |\n\
+             | 16:45-17:00 | band_c |\n\
+             | 17:00-17:15 | band_b |\n\
+             | 17:15-17:30 | band_a |\n"
+        );
+    }
+
+    #[test]
+    fn to_schedule_too_many_slots() {
+        let live_info = create_live(&[("band_a", &["a"])]);
+
+        let program = LiveProgram {
+            user_names: Vec::default(),
+            item: (0..256).map(|index| format!("band_{index}")).collect(),
+        };
+        let setting = RehearsalSetting::new(LocalDateTime::new(2024, 4, 6, 17, 30));
+        let result = program
+            .create_rehearsal_timetable(&setting)
+            .to_schedule(&live_info);
+        assert_eq!(
+            result.err(),
+            Some(SchedulerError::TooManyBlocks { block_count: 256 })
+        );
+    }
+}
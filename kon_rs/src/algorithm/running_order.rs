@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+
+use crate::{BandId, InstrumentType, LiveProgram};
+
+use super::LiveInfo;
+
+// 入れ替えに手間がかかる楽器の既定値
+pub(crate) const SETUP_INSTRUMENTS: InstrumentType = InstrumentType::DRUMS
+    .union(InstrumentType::KEYBOARD)
+    .union(InstrumentType::PIANO);
+
+// 前後のバンドで搬入・搬出が必要な楽器の数
+pub(crate) fn count_setup_changes(
+    lhs: InstrumentType,
+    rhs: InstrumentType,
+    setup_instruments: InstrumentType,
+) -> u32 {
+    ((lhs ^ rhs) & setup_instruments).bits().count_ones()
+}
+
+// 掛け持ちメンバーの転換が間に合わないときのペナルティ
+// 機材の入れ替え何回分よりも重く扱う
+const MEMBER_CHANGEOVER_WEIGHT: u32 = 100;
+
+/// ライブ当日の出演順
+pub struct RunningOrder {
+    band_ids: Vec<BandId>,
+
+    // 転換の時間が足りない掛け持ちメンバーの延べ人数
+    member_conflict_count: u32,
+
+    // ドラムやキーボードの搬入・搬出の回数
+    setup_change_count: u32,
+}
+
+impl RunningOrder {
+    /// 出演順に並んだバンド
+    pub fn band_ids(&self) -> &[BandId] {
+        &self.band_ids
+    }
+
+    /// 転換の時間が足りない掛け持ちメンバーの延べ人数
+    pub fn member_conflict_count(&self) -> u32 {
+        self.member_conflict_count
+    }
+
+    /// ドラムやキーボードの搬入・搬出の回数
+    pub fn setup_change_count(&self) -> u32 {
+        self.setup_change_count
+    }
+
+    /// 出演順を LiveProgram にします
+    ///
+    /// 出演者は初めて出演する順に並べます。
+    pub fn to_live_program(&self, live_info: &LiveInfo) -> LiveProgram {
+        let mut user_names: Vec<String> = Vec::default();
+        for band_id in &self.band_ids {
+            for member_id in live_info.band_member_ids(*band_id).unwrap_or_default() {
+                let Some(identifier) = live_info.user_identifier(*member_id) else {
+                    continue;
+                };
+                if !user_names.iter().any(|name| name == identifier) {
+                    user_names.push(identifier.to_string());
+                }
+            }
+        }
+
+        LiveProgram {
+            user_names,
+            item: self
+                .band_ids
+                .iter()
+                .map(|band_id| {
+                    live_info
+                        .band_name(*band_id)
+                        .unwrap_or_default()
+                        .to_string()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// ひとつのステージでの出演順を決めます
+///
+/// 掛け持ちメンバーが続けて出演しないようにしつつ、ドラムやキーボードの入れ替えが少ない順番を探します。
+/// メンバーの重複は LiveInfo のバンドのハッシュ値から判定します。
+pub struct RunningOrderPlanner {
+    // 掛け持ちメンバーがいるバンドの間に挟むバンドの数
+    changeover_gap: usize,
+
+    // 入れ替えに手間がかかる楽器
+    setup_instruments: InstrumentType,
+
+    band_instrument_table: HashMap<BandId, InstrumentType>,
+
+    // 先頭から順に固定するバンド
+    openers: Vec<BandId>,
+
+    // 最後の方に順に固定するバンド。末尾がトリ
+    closers: Vec<BandId>,
+}
+
+impl RunningOrderPlanner {
+    pub fn new() -> Self {
+        Self {
+            changeover_gap: 1,
+            setup_instruments: SETUP_INSTRUMENTS,
+            band_instrument_table: HashMap::default(),
+            openers: Vec::default(),
+            closers: Vec::default(),
+        }
+    }
+
+    /// 掛け持ちメンバーがいるバンドの間に最低限挟むバンドの数
+    ///
+    /// 0 なら連続して出演できます。
+    pub fn with_changeover_gap(mut self, changeover_gap: usize) -> Self {
+        self.changeover_gap = changeover_gap;
+        self
+    }
+
+    /// 入れ替えに手間がかかる楽器を指定します
+    pub fn with_setup_instruments(mut self, setup_instruments: InstrumentType) -> Self {
+        self.setup_instruments = setup_instruments;
+        self
+    }
+
+    /// バンドが使う楽器を指定します
+    ///
+    /// 指定しなかったバンドは LiveInfo の編成を使います。
+    pub fn with_band_instruments(mut self, band_id: BandId, instruments: InstrumentType) -> Self {
+        self.band_instrument_table.insert(band_id, instruments);
+        self
+    }
+
+    /// 先頭に固定するバンドを追加します
+    pub fn push_opener(mut self, band_id: BandId) -> Self {
+        self.openers.push(band_id);
+        self
+    }
+
+    /// 最後に固定するバンドを追加します。最後に追加したバンドがトリになります
+    pub fn push_closer(mut self, band_id: BandId) -> Self {
+        self.closers.push(band_id);
+        self
+    }
+
+    /// 出演順を決めます
+    ///
+    /// 存在しないバンドや重複して固定したバンドは無視します。
+    pub fn plan(&self, live_info: &LiveInfo) -> RunningOrder {
+        let band_ids = live_info.band_ids();
+        let hashes: Vec<u64> = band_ids
+            .iter()
+            .map(|id| live_info.band_hash(*id).unwrap_or_default())
+            .collect();
+        let instruments: Vec<InstrumentType> = band_ids
+            .iter()
+            .map(|id| {
+                self.band_instrument_table
+                    .get(id)
+                    .copied()
+                    .unwrap_or_else(|| live_info.band_instruments(*id))
+            })
+            .collect();
+
+        // 固定するバンドと並べ替えるバンドに分ける
+        let mut is_pinned = vec![false; band_ids.len()];
+        let mut pin = |band_id: &BandId| {
+            let index = band_ids.iter().position(|id| id == band_id)?;
+            if std::mem::replace(&mut is_pinned[index], true) {
+                return None;
+            }
+            Some(index)
+        };
+        let openers: Vec<usize> = self.openers.iter().filter_map(&mut pin).collect();
+        let closers: Vec<usize> = self.closers.iter().filter_map(&mut pin).collect();
+        let free: Vec<usize> = (0..band_ids.len()).filter(|x| !is_pinned[*x]).collect();
+
+        let evaluate = |order: &[usize]| {
+            let mut member_conflict_count = 0;
+            for (position, lhs) in order.iter().enumerate() {
+                for rhs in order.iter().skip(position + 1).take(self.changeover_gap) {
+                    member_conflict_count += (hashes[*lhs] & hashes[*rhs]).count_ones();
+                }
+            }
+            let setup_change_count: u32 = order
+                .windows(2)
+                .map(|x| {
+                    count_setup_changes(
+                        instruments[x[0]],
+                        instruments[x[1]],
+                        self.setup_instruments,
+                    )
+                })
+                .sum();
+            (member_conflict_count, setup_change_count)
+        };
+        let cost = |order: &[usize]| {
+            let (member_conflict_count, setup_change_count) = evaluate(order);
+            member_conflict_count * MEMBER_CHANGEOVER_WEIGHT + setup_change_count
+        };
+        let create_order = |free: &[usize]| -> Vec<usize> {
+            openers
+                .iter()
+                .chain(free)
+                .chain(&closers)
+                .copied()
+                .collect()
+        };
+
+        // 直前のバンドとの相性が最も良いバンドを順に選んで初期解にする
+        let mut current: Vec<usize> = Vec::default();
+        let mut rest = free.clone();
+        while !rest.is_empty() {
+            let (index, _) = rest
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, candidate)| {
+                    let mut order = openers.clone();
+                    order.extend(&current);
+                    order.push(**candidate);
+                    cost(&order)
+                })
+                .unwrap();
+            current.push(rest.remove(index));
+        }
+
+        // 入れ替えと挿し直しで改善できなくなるまで繰り返す
+        let mut current_cost = cost(&create_order(&current));
+        loop {
+            let mut best: Option<(Vec<usize>, u32)> = None;
+            for from in 0..current.len() {
+                for to in 0..current.len() {
+                    if from == to {
+                        continue;
+                    }
+
+                    let mut swapped = current.clone();
+                    swapped.swap(from, to);
+
+                    let mut moved = current.clone();
+                    let band_index = moved.remove(from);
+                    moved.insert(to, band_index);
+
+                    for candidate in [swapped, moved] {
+                        let candidate_cost = cost(&create_order(&candidate));
+                        let best_cost = best.as_ref().map_or(current_cost, |(_, cost)| *cost);
+                        if candidate_cost < best_cost {
+                            best = Some((candidate, candidate_cost));
+                        }
+                    }
+                }
+            }
+
+            let Some((order, order_cost)) = best else {
+                break;
+            };
+            current = order;
+            current_cost = order_cost;
+        }
+
+        let order = create_order(&current);
+        let (member_conflict_count, setup_change_count) = evaluate(&order);
+        RunningOrder {
+            band_ids: order.iter().map(|index| band_ids[*index]).collect(),
+            member_conflict_count,
+            setup_change_count,
+        }
+    }
+}
+
+impl Default for RunningOrderPlanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithm::tests::create_live;
+    use crate::{Band, BandMember, InstrumentType};
+
+    use super::RunningOrderPlanner;
+
+    // 掛け持ちメンバーがいるバンドは続けて出演しない
+    #[test]
+    fn member_changeover() {
+        let live_info = create_live(&[
+            ("band_a", &["a", "b"]),
+            ("band_b", &["a", "c"]),
+            ("band_c", &["d"]),
+            ("band_d", &["e"]),
+        ]);
+        let band_ids = live_info.band_ids();
+
+        let running_order = RunningOrderPlanner::new().plan(&live_info);
+        assert_eq!(running_order.band_ids().len(), 4);
+        assert_eq!(running_order.member_conflict_count(), 0);
+
+        let position = |index: usize| {
+            running_order
+                .band_ids()
+                .iter()
+                .position(|id| *id == band_ids[index])
+                .unwrap()
+        };
+        assert!(1 < position(0).abs_diff(position(1)));
+
+        // 間にふたつ挟む
+        let running_order = RunningOrderPlanner::new()
+            .with_changeover_gap(2)
+            .plan(&live_info);
+        assert_eq!(running_order.member_conflict_count(), 0);
+        let position = |index: usize| {
+            running_order
+                .band_ids()
+                .iter()
+                .position(|id| *id == band_ids[index])
+                .unwrap()
+        };
+        assert_eq!(position(0).abs_diff(position(1)), 3);
+    }
+
+    // ドラムを使うバンドと使わないバンドはなるべくまとめる
+    #[test]
+    fn setup_change() {
+        let live_info = create_live(&[
+            ("band_a", &["a"]),
+            ("band_b", &["b"]),
+            ("band_c", &["c"]),
+            ("band_d", &["d"]),
+        ]);
+        let band_ids = live_info.band_ids();
+
+        let running_order = RunningOrderPlanner::new()
+            .with_changeover_gap(0)
+            .with_band_instruments(band_ids[0], InstrumentType::DRUMS)
+            .with_band_instruments(band_ids[1], InstrumentType::VOCAL)
+            .with_band_instruments(band_ids[2], InstrumentType::DRUMS)
+            .with_band_instruments(
+                band_ids[3],
+                InstrumentType::VOCAL | InstrumentType::ACOUSTIC_GUITAR,
+            )
+            .plan(&live_info);
+        assert_eq!(running_order.setup_change_count(), 1);
+    }
+
+    // 指定しなければ LiveInfo の編成を使う
+    #[test]
+    fn setup_change_from_lineup() {
+        let mut live_info =
+            create_live(&[("band_a", &["a"]), ("band_b", &["b"]), ("band_c", &["c"])]);
+        live_info.set_band_lineups(&[
+            Band {
+                name: "band_a".to_string(),
+                members: vec![BandMember::new("a", InstrumentType::DRUMS)],
+            },
+            Band {
+                name: "band_c".to_string(),
+                members: vec![BandMember::new("c", InstrumentType::DRUMS)],
+            },
+        ]);
+        let band_ids = live_info.band_ids().to_vec();
+
+        let running_order = RunningOrderPlanner::new()
+            .with_changeover_gap(0)
+            .push_opener(band_ids[0])
+            .plan(&live_info);
+        assert!(running_order.band_ids() == [band_ids[0], band_ids[2], band_ids[1]]);
+        assert_eq!(running_order.setup_change_count(), 1);
+    }
+
+    // 固定したバンドは先頭と最後に入る
+    #[test]
+    fn pinned() {
+        let live_info = create_live(&[
+            ("band_a", &["a"]),
+            ("band_b", &["b"]),
+            ("band_c", &["c"]),
+            ("band_d", &["d"]),
+        ]);
+        let band_ids = live_info.band_ids();
+
+        let running_order = RunningOrderPlanner::new()
+            .push_opener(band_ids[3])
+            .push_closer(band_ids[1])
+            .push_closer(band_ids[0])
+            // 重複して固定したバンドは無視
+            .push_closer(band_ids[3])
+            .plan(&live_info);
+        assert!(running_order.band_ids() == [band_ids[3], band_ids[2], band_ids[1], band_ids[0]]);
+
+        let live_program = running_order.to_live_program(&live_info);
+        assert_eq!(
+            live_program.item,
+            vec!["band_d", "band_c", "band_b", "band_a"]
+        );
+        assert_eq!(live_program.user_names, vec!["d", "c", "b", "a"]);
+    }
+}
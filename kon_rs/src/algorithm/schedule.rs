@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{BandId, BlockId};
+
+use super::{LiveInfo, RoomMatrix, SpanTime};
+
+/// スケジュールの枠ひとつ分
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleEntry {
+    /// 何番目の時間帯か
+    pub span_index: usize,
+
+    /// 何番目の部屋か
+    pub room_index: usize,
+
+    pub block_id: BlockId,
+
+    /// 割り当てたバンド。空き枠なら None
+    pub band_id: Option<BandId>,
+}
+
+/// スケジューラーが出力する割り当て結果
+///
+/// 時間帯 (行) と部屋 (列) の表として扱えるように、割り当て表と部屋割りを合わせて保持します。
+#[derive(Clone)]
+pub struct Schedule {
+    // 枠 -> バンド
+    table: HashMap<BlockId, BandId>,
+
+    // 同じ探索で見つかったスケジュール同士で共有する
+    layout: Arc<ScheduleLayout>,
+}
+
+// 割り当てによらない部屋割りとバンドの情報
+struct ScheduleLayout {
+    // 時間帯 -> 部屋 -> 枠。部屋にその時間帯の枠がなければ None
+    grid: Vec<Vec<Option<BlockId>>>,
+
+    // 部屋名
+    room_names: Vec<Option<String>>,
+
+    // 時間帯の時刻
+    span_times: Vec<Option<SpanTime>>,
+
+    // 出演バンド (バンド名順)
+    band_ids: Vec<BandId>,
+
+    // バンド -> バンド名
+    band_name_table: HashMap<BandId, String>,
+
+    // バンド -> メンバーの識別子
+    band_member_table: HashMap<BandId, Vec<String>>,
+}
+
+impl ScheduleLayout {
+    fn new(room_matrix: &RoomMatrix, live_info: &LiveInfo) -> Self {
+        let grid = (0..room_matrix.spans().len())
+            .map(|span_index| {
+                room_matrix
+                    .rooms()
+                    .iter()
+                    .map(|room_id| {
+                        room_matrix
+                            .iter_room_blocks(*room_id)
+                            .nth(span_index)
+                            .copied()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let room_names = room_matrix
+            .rooms()
+            .iter()
+            .map(|room_id| room_matrix.room_name(*room_id).map(|name| name.to_string()))
+            .collect();
+        let span_times = room_matrix
+            .spans()
+            .iter()
+            .map(|span_id| room_matrix.span_time(*span_id))
+            .collect();
+
+        let band_ids = live_info.band_ids().to_vec();
+        let band_name_table = band_ids
+            .iter()
+            .filter_map(|band_id| Some((*band_id, live_info.band_name(*band_id)?.to_string())))
+            .collect();
+        let band_member_table = band_ids
+            .iter()
+            .map(|band_id| {
+                let members = live_info
+                    .band_member_ids(*band_id)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|user_id| live_info.user_identifier(*user_id))
+                    .map(|identifier| identifier.to_string())
+                    .collect();
+                (*band_id, members)
+            })
+            .collect();
+
+        Self {
+            grid,
+            room_names,
+            span_times,
+            band_ids,
+            band_name_table,
+            band_member_table,
+        }
+    }
+}
+
+impl Schedule {
+    pub fn new(
+        table: HashMap<BlockId, BandId>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> Self {
+        Self {
+            table,
+            layout: Arc::new(ScheduleLayout::new(room_matrix, live_info)),
+        }
+    }
+
+    // 同じ部屋割りとバンドの割り当て表をまとめて変換します
+    pub(crate) fn from_tables(
+        tables: Vec<HashMap<BlockId, BandId>>,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> Vec<Self> {
+        let layout = Arc::new(ScheduleLayout::new(room_matrix, live_info));
+        tables
+            .into_iter()
+            .map(|table| Self {
+                table,
+                layout: Arc::clone(&layout),
+            })
+            .collect()
+    }
+
+    // 部屋割りとバンドの情報を共有したまま、割り当て表だけを差し替えます
+    pub(crate) fn with_table(&self, table: HashMap<BlockId, BandId>) -> Self {
+        Self {
+            table,
+            layout: Arc::clone(&self.layout),
+        }
+    }
+
+    /// 枠 -> バンドの割り当て表
+    pub fn table(&self) -> &HashMap<BlockId, BandId> {
+        &self.table
+    }
+
+    pub fn span_count(&self) -> usize {
+        self.layout.grid.len()
+    }
+
+    pub fn room_count(&self) -> usize {
+        self.layout.grid.first().map_or(0, |rooms| rooms.len())
+    }
+
+    /// 部屋名。名前がなければ None
+    pub fn room_name(&self, room_index: usize) -> Option<&str> {
+        self.layout.room_names.get(room_index)?.as_deref()
+    }
+
+    /// 時間帯の時刻。時刻を指定していなければ None
+    pub fn span_time(&self, span_index: usize) -> Option<SpanTime> {
+        *self.layout.span_times.get(span_index)?
+    }
+
+    pub fn band_ids(&self) -> &[BandId] {
+        &self.layout.band_ids
+    }
+
+    pub fn band_name(&self, band_id: BandId) -> Option<&str> {
+        self.layout
+            .band_name_table
+            .get(&band_id)
+            .map(|name| name.as_str())
+    }
+
+    /// バンドのメンバーの識別子
+    pub fn band_members(&self, band_id: BandId) -> &[String] {
+        self.layout
+            .band_member_table
+            .get(&band_id)
+            .map_or(&[], |members| members.as_slice())
+    }
+
+    /// いずれかのバンドに所属するメンバーの識別子 (識別子順)
+    pub fn members(&self) -> Vec<&str> {
+        let mut members: Vec<&str> = self
+            .layout
+            .band_member_table
+            .values()
+            .flatten()
+            .map(|member| member.as_str())
+            .collect();
+        members.sort();
+        members.dedup();
+        members
+    }
+
+    /// 指定の時間帯・部屋の枠。枠がなければ None
+    pub fn entry(&self, span_index: usize, room_index: usize) -> Option<ScheduleEntry> {
+        let block_id = (*self.layout.grid.get(span_index)?.get(room_index)?)?;
+        Some(ScheduleEntry {
+            span_index,
+            room_index,
+            block_id,
+            band_id: self.table.get(&block_id).copied(),
+        })
+    }
+
+    /// 全ての枠を時間帯順、同じ時間帯では部屋順に列挙します
+    pub fn entries(&self) -> impl Iterator<Item = ScheduleEntry> + '_ {
+        (0..self.span_count()).flat_map(move |span_index| self.span(span_index))
+    }
+
+    /// 時間帯 (行) の枠を部屋順に列挙します
+    pub fn span(&self, span_index: usize) -> impl Iterator<Item = ScheduleEntry> + '_ {
+        (0..self.room_count()).filter_map(move |room_index| self.entry(span_index, room_index))
+    }
+
+    /// 部屋 (列) の枠を時間帯順に列挙します
+    pub fn room(&self, room_index: usize) -> impl Iterator<Item = ScheduleEntry> + '_ {
+        (0..self.span_count()).filter_map(move |span_index| self.entry(span_index, room_index))
+    }
+
+    /// メンバーの個人スケジュール。所属バンドが割り当てられた枠を時間帯順に列挙します
+    pub fn member(&self, identifier: &str) -> Vec<ScheduleEntry> {
+        self.entries()
+            .filter(|entry| {
+                entry.band_id.is_some_and(|band_id| {
+                    self.band_members(band_id)
+                        .iter()
+                        .any(|member| member == identifier)
+                })
+            })
+            .collect()
+    }
+
+    /// バンドが割り当てられた枠
+    pub fn band_entry(&self, band_id: BandId) -> Option<ScheduleEntry> {
+        self.entries().find(|entry| entry.band_id == Some(band_id))
+    }
+
+    /// どのバンドも割り当てられていない枠
+    pub fn free_entries(&self) -> Vec<ScheduleEntry> {
+        self.entries()
+            .filter(|entry| entry.band_id.is_none())
+            .collect()
+    }
+
+    /// どの枠にも割り当てられなかったバンド
+    pub fn unassigned_band_ids(&self) -> Vec<BandId> {
+        self.layout
+            .band_ids
+            .iter()
+            .filter(|band_id| !self.table.values().any(|id| id == *band_id))
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::algorithm::{create_live_info, RoomMatrix};
+
+    use super::Schedule;
+
+    // Room0 | Room1
+    // band_a | band_c
+    // band_b | ×
+    // (空き) | ×
+    #[test]
+    fn views() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string(), "x".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+            ("band_c".to_string(), vec!["c".to_string(), "x".to_string()]),
+            ("band_d".to_string(), vec!["d".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 3]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(3).push_room(1).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let band_ids = live_info.band_ids();
+        let room0: Vec<_> = room_matrix
+            .iter_room_blocks(room_matrix.rooms()[0])
+            .copied()
+            .collect();
+        let room1: Vec<_> = room_matrix
+            .iter_room_blocks(room_matrix.rooms()[1])
+            .copied()
+            .collect();
+        let table = HashMap::from([
+            (room0[0], band_ids[0]),
+            (room0[1], band_ids[1]),
+            (room1[0], band_ids[2]),
+        ]);
+        let schedule = Schedule::new(table, &room_matrix, &live_info);
+
+        assert_eq!(schedule.span_count(), 3);
+        assert_eq!(schedule.room_count(), 2);
+
+        // 時間帯 (行)
+        let span: Vec<_> = schedule
+            .span(0)
+            .map(|entry| entry.band_id.and_then(|id| schedule.band_name(id)))
+            .collect();
+        assert_eq!(span, vec![Some("band_a"), Some("band_c")]);
+        assert_eq!(schedule.span(1).count(), 1);
+
+        // 部屋 (列)
+        let room: Vec<_> = schedule
+            .room(0)
+            .map(|entry| entry.band_id.and_then(|id| schedule.band_name(id)))
+            .collect();
+        assert_eq!(room, vec![Some("band_a"), Some("band_b"), None]);
+
+        // 個人スケジュール
+        let member = schedule.member("x");
+        assert_eq!(member.len(), 2);
+        assert!(member.iter().all(|entry| entry.span_index == 0));
+        assert!(schedule.member("d").is_empty());
+
+        // 空き枠と割り当てられなかったバンド
+        let free_entries = schedule.free_entries();
+        assert_eq!(free_entries.len(), 1);
+        assert_eq!(
+            (free_entries[0].span_index, free_entries[0].room_index),
+            (2, 0)
+        );
+        assert!(schedule.unassigned_band_ids() == vec![band_ids[3]]);
+
+        let entry = schedule.band_entry(band_ids[1]).unwrap();
+        assert_eq!((entry.span_index, entry.room_index), (1, 0));
+    }
+}
@@ -1,14 +1,17 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+use itertools::Itertools;
 use uuid::Uuid;
 
 use crate::{BandId, BlockId};
 
-use super::detail::{
-    BandScheduleTraverseDecorator, MemberConflictTraverseDecorator, TreeTraverser,
+use super::detail::{ConstraintChain, SchedulerImpl};
+use super::{BandScheduleConstraint, IConstraint, LiveInfo, MemberConflictConstraint, RoomMatrix};
+use super::{
+    MultiObjective, ParetoFront, Schedule, ScheduleRanking, SchedulerError, ScoredSchedule,
+    TraverseOperation,
 };
-use super::{detail::SchedulerImpl, LiveInfo, RoomMatrix};
 
 pub struct SchedulerInfo {
     /// 走査総数
@@ -35,89 +38,223 @@ pub trait IScheduleCallback {
 
     fn on_progress(&mut self, _task_id: TaskId, _task_info: &TaskInfo);
 
-    fn on_assigned(
-        &mut self,
-        table: &HashMap<BlockId, BandId>,
-        room_matrix: &RoomMatrix,
-        live_info: &LiveInfo,
-    );
+    /// 制約を満たすスケジュールが見つかるたびに呼ばれます
+    ///
+    /// 同じ走査で見つかったスケジュールは部屋割りとバンドの情報を共有しているので、clone しても割り当て表の分しか複製しません。
+    fn on_assigned(&mut self, schedule: &Schedule, room_matrix: &RoomMatrix, live_info: &LiveInfo);
 
     fn on_completed(&mut self);
 }
 
+/// 制約を満たすスケジュールを全探索します
+///
+/// assign 系の関数は枠の数がバンドの数より少ないと SchedulerError::NotEnoughBlocks で失敗し、
+/// 全バンドを割り当てられなければ結果が空になります。一部のバンドだけでも割り当てたい場合は assign_partial を使ってください。
 pub struct Scheduler<T> {
     callback: T,
+
+    // 走査中に適用する制約
+    constraints: ConstraintChain,
 }
 
 impl Scheduler<()> {
+    /// バンドのスケジュールとメンバーの衝突を制約とするスケジューラーを生成します
     pub fn new() -> Self {
-        Self { callback: () }
+        Self::builder()
+            .push_constraint(BandScheduleConstraint)
+            .push_constraint(MemberConflictConstraint)
+            .build()
+    }
+
+    /// 制約を任意に組み合わせてスケジューラーを生成します
+    pub fn builder() -> SchedulerBuilder {
+        SchedulerBuilder::default()
+    }
+
+    /// 走査結果をコールバックで受け取るスケジューラーに変換します
+    pub fn with_callback<T>(self, callback: T) -> Scheduler<SchedulerImpl<ConstraintChain, T>>
+    where
+        T: IScheduleCallback + Send + Sync + Clone + 'static,
+    {
+        let scheduler_impl = SchedulerImpl::new(self.constraints.clone(), callback);
+        Scheduler {
+            callback: scheduler_impl,
+            constraints: self.constraints,
+        }
     }
 
     pub fn assign(
         &self,
         room_matrix: &RoomMatrix,
         live_info: &LiveInfo,
-    ) -> Vec<HashMap<BlockId, BandId>> {
-        // 枝刈り
-        let decorator = TreeTraverser::default();
-        let decorator = BandScheduleTraverseDecorator::new(decorator);
-        let decorator = MemberConflictTraverseDecorator::new(decorator);
+    ) -> Result<Vec<Schedule>, SchedulerError> {
+        let schedule_callback = Arc::new(Mutex::new(ScheduleCallbackMock::new()));
+        let mut scheduler_impl =
+            SchedulerImpl::new(self.constraints.clone(), Arc::clone(&schedule_callback));
+        scheduler_impl.assign(room_matrix, live_info)?;
+
+        let schedules = std::mem::take(&mut schedule_callback.lock().unwrap().assigned);
+        Ok(schedules)
+    }
+
+    /// 全バンドを割り当てられない場合は、一部のバンドを外して割り当てられるスケジュールをすべて返します
+    ///
+    /// 割り当てたバンド数が最も多く、その中で優先度の合計が最も高い組み合わせを採用します。
+    /// 割り当てられなかったバンドは Schedule::unassigned_band_ids で取得できます。
+    /// バンドの組み合わせごとに全探索するので、バンド数が多い場合は LocalSearchScheduler::assign_partial を使ってください。
+    pub fn assign_partial(
+        &self,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> Result<Vec<Schedule>, SchedulerError> {
+        let block_count = room_matrix.blocks().len();
+        if block_count == 0 {
+            return Ok(Vec::default());
+        }
+
+        // バンド数の多い組み合わせから順に試し、割り当てられた時点で打ち切る
+        let band_ids = live_info.band_ids();
+        for count in (0..=band_ids.len().min(block_count)).rev() {
+            let mut best: Option<(u64, Vec<HashMap<BlockId, BandId>>)> = None;
+            for ids in band_ids.iter().copied().combinations(count) {
+                let priority: u64 = ids
+                    .iter()
+                    .map(|id| live_info.band_priority(*id) as u64)
+                    .sum();
+                if best
+                    .as_ref()
+                    .is_some_and(|(best_priority, _)| priority < *best_priority)
+                {
+                    continue;
+                }
+
+                let tables = self.assign_subset(&ids, room_matrix, live_info)?;
+                if tables.is_empty() {
+                    continue;
+                }
+                match &mut best {
+                    Some((best_priority, best_tables)) if *best_priority == priority => {
+                        best_tables.extend(tables)
+                    }
+                    _ => best = Some((priority, tables)),
+                }
+            }
+
+            if let Some((_, tables)) = best {
+                return Ok(Schedule::from_tables(tables, room_matrix, live_info));
+            }
+        }
+
+        Ok(Vec::default())
+    }
+
+    // 指定したバンドだけを割り当てた割り当て表をすべて返す
+    fn assign_subset(
+        &self,
+        band_ids: &[BandId],
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> Result<Vec<HashMap<BlockId, BandId>>, SchedulerError> {
+        // 余った枠は空き枠用のバンドで埋めて全探索する
+        let (partial_live_info, free_band_ids) = live_info.with_bands(
+            band_ids,
+            room_matrix.blocks().len() - band_ids.len(),
+            room_matrix,
+        );
+
+        // 空き枠用のバンドは区別しないので、並びを固定して同じ割り当て表を何度も走査しない
+        let mut constraints = self.constraints.clone();
+        constraints.push(Arc::new(FreeBlockOrderConstraint {
+            first_free_index: band_ids.len() as i32,
+        }));
 
         let schedule_callback = Arc::new(Mutex::new(ScheduleCallbackMock::new()));
-        let mut scheduler_impl = SchedulerImpl::new(decorator, Arc::clone(&schedule_callback));
-        let _ = scheduler_impl.assign(room_matrix, live_info);
+        let mut scheduler_impl = SchedulerImpl::new(constraints, Arc::clone(&schedule_callback));
+        scheduler_impl.assign(room_matrix, &partial_live_info)?;
+
+        let tables = std::mem::take(&mut schedule_callback.lock().unwrap().assigned)
+            .into_iter()
+            .map(|schedule| {
+                let mut table = schedule.table().clone();
+                table.retain(|_, band_id| !free_band_ids.contains(band_id));
+                table
+            })
+            .collect();
+        Ok(tables)
+    }
+
+    /// 全探索した結果のうち、評価軸の上でパレート最適なスケジュールだけを返します
+    pub fn assign_pareto(
+        &self,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+        objective: MultiObjective,
+    ) -> Result<Vec<ScoredSchedule>, SchedulerError> {
+        let pareto_front = Arc::new(Mutex::new(ParetoFront::new(objective)));
+        let mut scheduler_impl =
+            SchedulerImpl::new(self.constraints.clone(), Arc::clone(&pareto_front));
+        scheduler_impl.assign(room_matrix, live_info)?;
+
+        let schedules = pareto_front.lock().unwrap().schedules();
+        Ok(schedules)
+    }
 
-        let x = schedule_callback.lock().unwrap().assigned.clone();
-        x
+    /// 全探索した結果のうち、評価値の重み付き和が高い順に count 個のスケジュールを返します
+    pub fn assign_ranked(
+        &self,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+        objective: MultiObjective,
+        count: usize,
+    ) -> Result<Vec<ScoredSchedule>, SchedulerError> {
+        let ranking = Arc::new(Mutex::new(ScheduleRanking::new(objective, count)));
+        let mut scheduler_impl = SchedulerImpl::new(self.constraints.clone(), Arc::clone(&ranking));
+        scheduler_impl.assign(room_matrix, live_info)?;
+
+        let schedules = ranking.lock().unwrap().schedules().to_vec();
+        Ok(schedules)
     }
 
     pub async fn assign_async(
         &self,
         room_matrix: Arc<RoomMatrix>,
         live_info: Arc<LiveInfo>,
-    ) -> Vec<HashMap<BlockId, BandId>> {
-        // 枝刈り
-        let decorator = TreeTraverser::default();
-        let decorator = BandScheduleTraverseDecorator::new(decorator);
-        let decorator = MemberConflictTraverseDecorator::new(decorator);
-
+    ) -> Result<Vec<Schedule>, SchedulerError> {
         let schedule_callback = Arc::new(Mutex::new(ScheduleCallbackMock::new()));
-        let mut scheduler_impl = SchedulerImpl::new(decorator, Arc::clone(&schedule_callback));
-        let _ = scheduler_impl.assign_async(room_matrix, live_info).await;
+        let mut scheduler_impl =
+            SchedulerImpl::new(self.constraints.clone(), Arc::clone(&schedule_callback));
+        scheduler_impl
+            .assign_async(Arc::clone(&room_matrix), Arc::clone(&live_info))
+            .await?;
+
+        let schedules = std::mem::take(&mut schedule_callback.lock().unwrap().assigned);
+        Ok(schedules)
+    }
+}
 
-        let x = schedule_callback.lock().unwrap().assigned.clone();
-        x
+impl Default for Scheduler<()> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl<T>
-    Scheduler<
-        SchedulerImpl<
-            MemberConflictTraverseDecorator<BandScheduleTraverseDecorator<TreeTraverser>>,
-            T,
-        >,
-    >
+impl<T> Scheduler<SchedulerImpl<ConstraintChain, T>>
 where
     T: IScheduleCallback + Send + Sync + Clone + 'static,
 {
     pub fn new_with_callback(callback: T) -> Self {
-        // 枝刈り
-        let decorator = TreeTraverser::default();
-        let decorator = BandScheduleTraverseDecorator::new(decorator);
-        let decorator = MemberConflictTraverseDecorator::new(decorator);
-
-        let scheduler_impl = SchedulerImpl::new(decorator, callback);
-        Self {
-            callback: scheduler_impl,
-        }
+        Scheduler::new().with_callback(callback)
     }
 
-    pub fn assign(&mut self, room_matrix: &RoomMatrix, live_info: &LiveInfo)
+    pub fn assign(
+        &mut self,
+        room_matrix: &RoomMatrix,
+        live_info: &LiveInfo,
+    ) -> Result<(), SchedulerError>
     where
         T: IScheduleCallback + Send + Sync + 'static,
     {
-        let _ = self.callback.assign(room_matrix, live_info);
+        self.callback.assign(room_matrix, live_info)
     }
 
     // #[cfg(not(target_arch = "wasm32"))]
@@ -127,16 +264,66 @@ where
         live_info: Arc<LiveInfo>,
         sub_tree_depth: usize,
         task_count: usize,
-    ) {
-        let _ = self
-            .callback
+    ) -> Result<(), SchedulerError> {
+        self.callback
             .assign_async_with_params(room_matrix, live_info, sub_tree_depth, task_count)
-            .await;
+            .await
+    }
+}
+
+// 空き枠用のバンドが番号順に並んだ割り当てだけを走査する
+// 空き枠用のバンドは LiveInfo::with_bands で末尾に追加されるので first_free_index 以降のインデックスになる
+struct FreeBlockOrderConstraint {
+    first_free_index: i32,
+}
+
+impl IConstraint for FreeBlockOrderConstraint {
+    fn invoke(
+        &self,
+        indicies: &[i32],
+        _room_matrix: &RoomMatrix,
+        _live_info: &LiveInfo,
+    ) -> TraverseOperation {
+        let mut last_free_index = None;
+        for (position, band_index) in indicies.iter().enumerate() {
+            if *band_index < self.first_free_index {
+                continue;
+            }
+            if last_free_index.is_some_and(|last| *band_index < last) {
+                return TraverseOperation::Skip(position + 1);
+            }
+            last_free_index = Some(*band_index);
+        }
+
+        TraverseOperation::Next
+    }
+}
+
+/// 制約を登録順に適用するスケジューラーを構築します
+#[derive(Default)]
+pub struct SchedulerBuilder {
+    constraints: ConstraintChain,
+}
+
+impl SchedulerBuilder {
+    pub fn build(self) -> Scheduler<()> {
+        Scheduler {
+            callback: (),
+            constraints: self.constraints,
+        }
+    }
+
+    pub fn push_constraint<T>(mut self, constraint: T) -> Self
+    where
+        T: IConstraint + Send + Sync + 'static,
+    {
+        self.constraints.push(Arc::new(constraint));
+        self
     }
 }
 
 struct ScheduleCallbackMock {
-    assigned: Vec<HashMap<BlockId, BandId>>,
+    assigned: Vec<Schedule>,
 }
 
 impl ScheduleCallbackMock {
@@ -154,11 +341,11 @@ impl IScheduleCallback for Arc<Mutex<ScheduleCallbackMock>> {
 
     fn on_assigned(
         &mut self,
-        table: &HashMap<BlockId, BandId>,
+        schedule: &Schedule,
         _room_matrix: &RoomMatrix,
         _live_info: &LiveInfo,
     ) {
-        self.lock().unwrap().assigned.push(table.clone());
+        self.lock().unwrap().assigned.push(schedule.clone());
     }
 
     fn on_completed(&mut self) {}
@@ -168,7 +355,10 @@ impl IScheduleCallback for Arc<Mutex<ScheduleCallbackMock>> {
 mod tests {
     use std::{collections::HashMap, sync::Arc};
 
-    use crate::algorithm::{create_live_info, RoomMatrix, Scheduler};
+    use crate::algorithm::{
+        create_live_info, BandScheduleConstraint, MemberConflictConstraint, PairConstraint,
+        PairRelation, RoomMatrix, Scheduler, SchedulerError,
+    };
 
     #[test]
     fn simple() {
@@ -181,10 +371,10 @@ mod tests {
             .map(|key| (key.to_string(), vec![true; 16]))
             .collect();
         let room_matrix = RoomMatrix::builder().push_room(2).build();
-        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix);
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
 
         let scheduler = Scheduler::new();
-        let result = scheduler.assign(&room_matrix, &live_info);
+        let result = scheduler.assign(&room_matrix, &live_info).unwrap();
         assert_eq!(result.len(), 2);
     }
 
@@ -200,11 +390,138 @@ mod tests {
             .map(|key| (key.to_string(), vec![true; 16]))
             .collect();
         let room_matrix = RoomMatrix::builder().push_room(1).build();
-        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix);
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
 
         let scheduler = Scheduler::new();
         let result = scheduler.assign(&room_matrix, &live_info);
-        assert_eq!(result.len(), 0);
+        assert!(matches!(
+            result,
+            Err(SchedulerError::NotEnoughBlocks {
+                block_count: 1,
+                band_count: 2
+            })
+        ));
+    }
+
+    // 枠が足りなければ優先度の高いバンドだけを割り当てる
+    #[test]
+    fn partial() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 16]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(1).build();
+        let mut live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        // 優先度が同じならどちらかを割り当てた結果をすべて返す
+        let result = Scheduler::new()
+            .assign_partial(&room_matrix, &live_info)
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result.iter().all(|x| x.unassigned_band_ids().len() == 1));
+
+        let band_b = live_info.band_ids()[1];
+        live_info.set_band_priority(band_b, 2);
+        let result = Scheduler::new()
+            .assign_partial(&room_matrix, &live_info)
+            .unwrap();
+        assert_eq!(result.len(), 1);
+        assert!(result[0].unassigned_band_ids() == vec![live_info.band_ids()[0]]);
+    }
+
+    // 入れる枠がないバンドは外して、残りのバンドは空き枠を含めて割り当てる
+    #[test]
+    fn partial_with_free_blocks() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+        ]);
+        let band_schedule = HashMap::from([
+            ("band_a".to_string(), vec![false, false]),
+            ("band_b".to_string(), vec![true, true]),
+        ]);
+        let room_matrix = RoomMatrix::builder().push_room(2).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+        let band_a = live_info
+            .band_ids()
+            .iter()
+            .copied()
+            .find(|id| live_info.band_name(*id) == Some("band_a"))
+            .unwrap();
+
+        // 全バンドは割り当てられない
+        let result = Scheduler::new().assign(&room_matrix, &live_info).unwrap();
+        assert!(result.is_empty());
+
+        // band_b を 2 つの枠のどちらかに割り当てる
+        let result = Scheduler::new()
+            .assign_partial(&room_matrix, &live_info)
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        for schedule in &result {
+            assert!(schedule.unassigned_band_ids() == vec![band_a]);
+            assert_eq!(schedule.free_entries().len(), 1);
+        }
+    }
+
+    // 空き枠が複数あっても、空き枠の並びだけが異なる結果は返さない
+    #[test]
+    fn partial_with_multiple_free_blocks() {
+        let band_table = HashMap::from([("band_a".to_string(), vec!["a".to_string()])]);
+        let band_schedule = HashMap::from([("band_a".to_string(), vec![true; 3])]);
+        let room_matrix = RoomMatrix::builder().push_room(3).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let result = Scheduler::new()
+            .assign_partial(&room_matrix, &live_info)
+            .unwrap();
+        assert_eq!(result.len(), 3);
+        for schedule in &result {
+            assert_eq!(schedule.table().len(), 1);
+            assert_eq!(schedule.free_entries().len(), 2);
+        }
+    }
+
+    // 先頭のバンドを外した組み合わせでもバンド同士の関係の制約を適用する
+    #[test]
+    fn partial_with_pair_constraint() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+            ("band_c".to_string(), vec!["c".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 2]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(2).build();
+        let mut live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let band_a = live_info.band_ids()[0];
+        let band_b = live_info.band_ids()[1];
+        let band_c = live_info.band_ids()[2];
+        live_info.set_band_priority(band_a, 0);
+
+        let pair_constraint = PairConstraint::new(&room_matrix, &live_info).push(
+            band_c,
+            band_b,
+            PairRelation::Before,
+        );
+        let result = Scheduler::builder()
+            .push_constraint(BandScheduleConstraint)
+            .push_constraint(MemberConflictConstraint)
+            .push_constraint(pair_constraint)
+            .build()
+            .assign_partial(&room_matrix, &live_info)
+            .unwrap();
+        assert_eq!(result.len(), 1);
+        assert!(result[0].unassigned_band_ids() == vec![band_a]);
+        assert!(result[0].table()[&room_matrix.blocks()[0]] == band_c);
+        assert!(result[0].table()[&room_matrix.blocks()[1]] == band_b);
     }
 
     #[test]
@@ -231,10 +548,10 @@ mod tests {
             .map(|key| (key.to_string(), vec![true; 16]))
             .collect();
         let room_matrix = RoomMatrix::builder().push_room(2).push_room(1).build();
-        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix);
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
 
         let scheduler = Scheduler::new();
-        let result = scheduler.assign(&room_matrix, &live_info);
+        let result = scheduler.assign(&room_matrix, &live_info).unwrap();
         assert_eq!(result.len(), 2);
     }
 
@@ -255,10 +572,34 @@ mod tests {
             ("band_c".to_string(), vec![true, false]),
         ]);
         let room_matrix = RoomMatrix::builder().push_room(2).push_room(1).build();
-        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix);
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
 
         let scheduler = Scheduler::new();
-        let result = scheduler.assign(&room_matrix, &live_info);
+        let result = scheduler.assign(&room_matrix, &live_info).unwrap();
+        assert_eq!(result.len(), 2);
+    }
+
+    // メンバー衝突の制約を外すと同時刻に同じメンバーのバンドが入れる
+    #[test]
+    fn without_member_conflict() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["aaa_aaa".to_string()]),
+            ("band_b".to_string(), vec!["aaa_aaa".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 16]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(1).push_room(1).build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let result = Scheduler::new().assign(&room_matrix, &live_info).unwrap();
+        assert_eq!(result.len(), 0);
+
+        let scheduler = Scheduler::builder()
+            .push_constraint(BandScheduleConstraint)
+            .build();
+        let result = scheduler.assign(&room_matrix, &live_info).unwrap();
         assert_eq!(result.len(), 2);
     }
 
@@ -297,7 +638,7 @@ mod tests {
             .map(|key| (key.to_string(), vec![true; 16]))
             .collect();
         let room_matrix = Arc::new(RoomMatrix::builder().push_room(3).push_room(1).build());
-        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix);
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
         let live_info = Arc::new(live_info);
 
         tokio::runtime::Builder::new_multi_thread()
@@ -306,7 +647,10 @@ mod tests {
             .expect("Failed building the Runtime")
             .block_on(async {
                 let scheduler = Scheduler::new();
-                let result = scheduler.assign_async(room_matrix, live_info).await;
+                let result = scheduler
+                    .assign_async(room_matrix, live_info)
+                    .await
+                    .unwrap();
                 // let result = scheduler.assign(&room_matrix, &live_info);
                 assert_eq!(result.len(), 4);
             });
@@ -343,7 +687,7 @@ mod tests {
                 .push_room(1)
                 .build(),
         );
-        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix);
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
         let live_info = Arc::new(live_info);
 
         // 並列実行
@@ -353,7 +697,10 @@ mod tests {
             .expect("Failed building the Runtime")
             .block_on(async {
                 let scheduler = Scheduler::new();
-                let result = scheduler.assign_async(room_matrix, live_info).await;
+                let result = scheduler
+                    .assign_async(room_matrix, live_info)
+                    .await
+                    .unwrap();
                 // let result = scheduler.assign(&room_matrix, &live_info);
                 assert_eq!(result.len(), 2764800);
             });
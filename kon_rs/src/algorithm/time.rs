@@ -0,0 +1,264 @@
+use std::fmt;
+
+/// タイムゾーンを持たない日時
+///
+/// 練習会の会場の現地時刻として扱います。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LocalDateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl LocalDateTime {
+    /// 存在しない日時を指定すると panic します。入力を検証するなら try_new を使ってください
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8) -> Self {
+        Self::try_new(year, month, day, hour, minute).unwrap()
+    }
+
+    /// 存在しない日付や 24 時以降、60 分以降の時刻なら None
+    pub fn try_new(year: u16, month: u8, day: u8, hour: u8, minute: u8) -> Option<Self> {
+        if !(1..=12).contains(&month)
+            || !(1..=days_in_month(year, month)).contains(&day)
+            || 24 <= hour
+            || 60 <= minute
+        {
+            return None;
+        }
+
+        Some(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+        })
+    }
+
+    /// 1970-01-01 00:00 からの経過秒数から生成します。負の値なら 1970 年より前の日時です
+    ///
+    /// 年が 0 から 65535 の範囲に収まらなければ None を返します。
+    pub fn from_unix_time(seconds: i64) -> Option<Self> {
+        let days = seconds.div_euclid(86400);
+        let seconds_of_day = seconds.rem_euclid(86400);
+
+        // グレゴリオ暦の日付に変換
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days.checked_add(719468)?;
+        let era = z.div_euclid(146097);
+        let day_of_era = z.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Some(Self {
+            year: u16::try_from(year).ok()?,
+            month: month as u8,
+            day: day as u8,
+            hour: (seconds_of_day / 3600) as u8,
+            minute: (seconds_of_day % 3600 / 60) as u8,
+        })
+    }
+
+    /// 1970-01-01 00:00 からの経過秒数。1970 年より前なら負の値になります
+    pub fn to_unix_time(&self) -> i64 {
+        // グレゴリオ暦の日付から日数に変換
+        // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month = self.month as i64;
+        let month_index = if month > 2 { month - 3 } else { month + 9 };
+        let day_of_year = (153 * month_index + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146097 + day_of_era - 719468;
+
+        days * 86400 + self.hour as i64 * 3600 + self.minute as i64 * 60
+    }
+
+    /// 指定した分数だけずらした日時。負の値なら前にずらします
+    ///
+    /// 年が表現できる範囲を超えると panic します。
+    pub fn add_minutes(&self, minutes: i64) -> Self {
+        self.checked_add_minutes(minutes).unwrap()
+    }
+
+    /// 指定した分数だけずらした日時。年が表現できる範囲を超えるなら None
+    pub fn checked_add_minutes(&self, minutes: i64) -> Option<Self> {
+        let seconds = minutes.checked_mul(60)?.checked_add(self.to_unix_time())?;
+        Self::from_unix_time(seconds)
+    }
+
+    /// other から何分経っているか。other の方が後なら負の値になります
+    pub fn minutes_since(&self, other: &LocalDateTime) -> i64 {
+        (self.to_unix_time() - other.to_unix_time()) / 60
+    }
+
+    /// iCalendar の DATE-TIME 形式 (例: 20240401T100000)
+    pub fn to_ical(&self) -> String {
+        format!(
+            "{:04}{:02}{:02}T{:02}{:02}00",
+            self.year, self.month, self.day, self.hour, self.minute
+        )
+    }
+}
+
+// 月の日数。2 月はうるう年なら 29 日
+fn days_in_month(year: u16, month: u8) -> u8 {
+    let is_leap_year =
+        year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    match month {
+        2 if is_leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for LocalDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute
+        )
+    }
+}
+
+/// 時間帯の開始・終了時刻
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanTime {
+    pub start: LocalDateTime,
+    pub end: LocalDateTime,
+}
+
+impl SpanTime {
+    pub fn new(start: LocalDateTime, end: LocalDateTime) -> Self {
+        Self { start, end }
+    }
+
+    /// 日付を省いた時刻の範囲 (例: 10:00-11:30)
+    pub fn to_time_string(&self) -> String {
+        format!(
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start.hour, self.start.minute, self.end.hour, self.end.minute
+        )
+    }
+}
+
+impl fmt::Display for SpanTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 同じ日なら終了時刻は時刻だけ表示
+        if (self.start.year, self.start.month, self.start.day)
+            == (self.end.year, self.end.month, self.end.day)
+        {
+            write!(
+                f,
+                "{}-{:02}:{:02}",
+                self.start, self.end.hour, self.end.minute
+            )
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocalDateTime, SpanTime};
+
+    #[test]
+    fn from_unix_time() {
+        assert_eq!(
+            LocalDateTime::from_unix_time(0),
+            Some(LocalDateTime::new(1970, 1, 1, 0, 0))
+        );
+
+        // 2024-02-29 12:34 (うるう日)
+        assert_eq!(
+            LocalDateTime::from_unix_time(1709210040),
+            Some(LocalDateTime::new(2024, 2, 29, 12, 34))
+        );
+
+        // 1970 年より前
+        let date_time = LocalDateTime::new(1969, 12, 31, 23, 59);
+        assert_eq!(date_time.to_unix_time(), -60);
+        assert_eq!(LocalDateTime::from_unix_time(-60), Some(date_time));
+
+        // 年が表現できない
+        assert_eq!(LocalDateTime::from_unix_time(i64::MIN), None);
+        assert_eq!(LocalDateTime::from_unix_time(i64::MAX), None);
+    }
+
+    #[test]
+    fn try_new() {
+        assert!(LocalDateTime::try_new(2024, 2, 29, 23, 59).is_some());
+        assert!(LocalDateTime::try_new(2023, 2, 29, 0, 0).is_none());
+        assert!(LocalDateTime::try_new(1900, 2, 29, 0, 0).is_none());
+        assert!(LocalDateTime::try_new(2024, 4, 31, 0, 0).is_none());
+        assert!(LocalDateTime::try_new(2024, 0, 1, 0, 0).is_none());
+        assert!(LocalDateTime::try_new(2024, 13, 1, 0, 0).is_none());
+        assert!(LocalDateTime::try_new(2024, 4, 0, 0, 0).is_none());
+        assert!(LocalDateTime::try_new(2024, 4, 1, 24, 0).is_none());
+        assert!(LocalDateTime::try_new(2024, 4, 1, 0, 60).is_none());
+    }
+
+    #[test]
+    fn add_minutes() {
+        let date_time = LocalDateTime::new(2024, 2, 28, 23, 50);
+        assert_eq!(date_time.to_unix_time(), 1709164200);
+        assert_eq!(
+            date_time.add_minutes(20),
+            LocalDateTime::new(2024, 2, 29, 0, 10)
+        );
+        assert_eq!(
+            date_time.add_minutes(-1430),
+            LocalDateTime::new(2024, 2, 28, 0, 0)
+        );
+        assert_eq!(
+            LocalDateTime::new(2024, 3, 1, 0, 10).minutes_since(&date_time),
+            24 * 60 + 20
+        );
+        assert_eq!(
+            LocalDateTime::new(2023, 12, 31, 23, 59).add_minutes(1),
+            LocalDateTime::new(2024, 1, 1, 0, 0)
+        );
+
+        // 1970 年をまたいで前にずらす
+        assert_eq!(
+            LocalDateTime::new(1970, 1, 1, 0, 10).add_minutes(-20),
+            LocalDateTime::new(1969, 12, 31, 23, 50)
+        );
+
+        // 年が表現できる範囲を超える
+        let first = LocalDateTime::new(0, 1, 1, 0, 0);
+        assert_eq!(first.checked_add_minutes(-1), None);
+        assert_eq!(first.checked_add_minutes(i64::MAX), None);
+        assert_eq!(
+            LocalDateTime::new(65535, 12, 31, 23, 59).checked_add_minutes(1),
+            None
+        );
+    }
+
+    #[test]
+    fn format() {
+        let start = LocalDateTime::new(2024, 4, 1, 10, 0);
+        let end = LocalDateTime::new(2024, 4, 1, 11, 30);
+        assert_eq!(start.to_ical(), "20240401T100000");
+        assert_eq!(
+            SpanTime::new(start, end).to_string(),
+            "2024-04-01 10:00-11:30"
+        );
+        assert_eq!(SpanTime::new(start, end).to_time_string(), "10:00-11:30");
+    }
+}
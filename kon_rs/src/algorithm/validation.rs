@@ -0,0 +1,248 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::{LiveInfoError, RoomMatrix};
+
+/// スケジューリングはできるが確認してほしい入力
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LiveInfoWarning {
+    /// 同じメンバーがバンドに複数回登録されている (重複は無視されます)
+    DuplicateMember { band_name: String, member: String },
+
+    /// スケジュールの長さが時間帯の数より長い (余った分は無視されます)
+    ScheduleTooLong {
+        band_name: String,
+        length: usize,
+        span_count: usize,
+    },
+
+    /// 参加できる時間帯がひとつもない
+    NoAvailableSpan { band_name: String },
+}
+
+impl fmt::Display for LiveInfoWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiveInfoWarning::DuplicateMember { band_name, member } => {
+                write!(f, "{member} is listed more than once in {band_name}")
+            }
+            LiveInfoWarning::ScheduleTooLong {
+                band_name,
+                length,
+                span_count,
+            } => write!(
+                f,
+                "schedule of {band_name} has {length} entries but there are only {span_count} spans"
+            ),
+            LiveInfoWarning::NoAvailableSpan { band_name } => {
+                write!(f, "band has no available span: {band_name}")
+            }
+        }
+    }
+}
+
+/// create_live_info の入力を検証した結果
+#[derive(Debug, Default, Clone)]
+pub struct ValidationReport {
+    /// LiveInfo を構築できない問題
+    pub errors: Vec<LiveInfoError>,
+
+    /// LiveInfo は構築できるが確認してほしい問題
+    pub warnings: Vec<LiveInfoWarning>,
+}
+
+impl ValidationReport {
+    /// エラーがなければ create_live_info は成功します
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+}
+
+/// スケジューリングの前に入力をまとめて検証します
+///
+/// 最初の問題で止まらずに、見つかった問題をすべて報告します。バンドはバンド名順に検証します。
+pub fn validate_live_info(
+    band_table: &HashMap<String, Vec<String>>,
+    band_schedule_table: &HashMap<String, Vec<bool>>,
+    room_matrix: &RoomMatrix,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    // メンバーごとにハッシュのビットを割り振るので 64 人までしか扱えない
+    let user_count = band_table
+        .values()
+        .flatten()
+        .collect::<HashSet<&String>>()
+        .len();
+    if user_count > u64::BITS as usize {
+        report
+            .errors
+            .push(LiveInfoError::TooManyUsers { user_count });
+    }
+
+    // スケジュール表にしかないバンド
+    let mut unknown_bands: Vec<&String> = band_schedule_table
+        .keys()
+        .filter(|band_name| !band_table.contains_key(*band_name))
+        .collect();
+    unknown_bands.sort();
+    for band_name in unknown_bands {
+        report.errors.push(LiveInfoError::UnknownBand {
+            band_name: band_name.clone(),
+        });
+    }
+
+    let mut bands: Vec<&String> = band_table.keys().collect();
+    bands.sort();
+
+    let span_count = room_matrix.spans().len();
+    for band_name in bands {
+        // メンバー
+        let members = &band_table[band_name];
+        if members.is_empty() {
+            report.errors.push(LiveInfoError::MissingMember {
+                band_name: band_name.clone(),
+            });
+        }
+
+        let mut member_set = HashSet::new();
+        let mut duplicated_set = HashSet::new();
+        for member in members {
+            if !member_set.insert(member) && duplicated_set.insert(member) {
+                report.warnings.push(LiveInfoWarning::DuplicateMember {
+                    band_name: band_name.clone(),
+                    member: member.clone(),
+                });
+            }
+        }
+
+        // スケジュール
+        let Some(schedule) = band_schedule_table.get(band_name) else {
+            report.errors.push(LiveInfoError::MissingSchedule {
+                band_name: band_name.clone(),
+            });
+            continue;
+        };
+
+        if schedule.len() < span_count {
+            report.errors.push(LiveInfoError::ScheduleTooShort {
+                band_name: band_name.clone(),
+                length: schedule.len(),
+                span_count,
+            });
+            continue;
+        }
+
+        if schedule.len() > span_count {
+            report.warnings.push(LiveInfoWarning::ScheduleTooLong {
+                band_name: band_name.clone(),
+                length: schedule.len(),
+                span_count,
+            });
+        }
+
+        if !schedule
+            .iter()
+            .take(span_count)
+            .any(|is_available| *is_available)
+        {
+            report.warnings.push(LiveInfoWarning::NoAvailableSpan {
+                band_name: band_name.clone(),
+            });
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::algorithm::{LiveInfoError, RoomMatrix};
+
+    use super::{validate_live_info, LiveInfoWarning};
+
+    #[test]
+    fn valid() {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["a".to_string(), "b".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 2]))
+            .collect();
+        let room_matrix = RoomMatrix::builder().push_room(2).build();
+
+        let report = validate_live_info(&band_table, &band_schedule, &room_matrix);
+        assert!(report.is_valid());
+        assert!(report.is_empty());
+    }
+
+    // 問題はまとめて報告される
+    #[test]
+    fn collect_all() {
+        let band_table = HashMap::from([
+            (
+                "band_a".to_string(),
+                vec!["a".to_string(), "b".to_string(), "a".to_string()],
+            ),
+            ("band_b".to_string(), Vec::default()),
+            ("band_c".to_string(), vec!["c".to_string()]),
+            ("band_d".to_string(), vec!["d".to_string()]),
+            ("band_e".to_string(), vec!["e".to_string()]),
+        ]);
+        let band_schedule = HashMap::from([
+            ("band_a".to_string(), vec![true, true, false]),
+            ("band_b".to_string(), vec![true, true]),
+            ("band_d".to_string(), vec![true]),
+            ("band_e".to_string(), vec![false, false]),
+            ("band_x".to_string(), vec![true, true]),
+        ]);
+        let room_matrix = RoomMatrix::builder().push_room(2).build();
+
+        let report = validate_live_info(&band_table, &band_schedule, &room_matrix);
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.errors,
+            vec![
+                LiveInfoError::UnknownBand {
+                    band_name: "band_x".to_string()
+                },
+                LiveInfoError::MissingMember {
+                    band_name: "band_b".to_string()
+                },
+                LiveInfoError::MissingSchedule {
+                    band_name: "band_c".to_string()
+                },
+                LiveInfoError::ScheduleTooShort {
+                    band_name: "band_d".to_string(),
+                    length: 1,
+                    span_count: 2
+                },
+            ]
+        );
+        assert_eq!(
+            report.warnings,
+            vec![
+                LiveInfoWarning::DuplicateMember {
+                    band_name: "band_a".to_string(),
+                    member: "a".to_string()
+                },
+                LiveInfoWarning::ScheduleTooLong {
+                    band_name: "band_a".to_string(),
+                    length: 3,
+                    span_count: 2
+                },
+                LiveInfoWarning::NoAvailableSpan {
+                    band_name: "band_e".to_string()
+                },
+            ]
+        );
+    }
+}
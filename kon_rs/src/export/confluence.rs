@@ -0,0 +1,60 @@
+use crate::algorithm::Schedule;
+
+use super::html::escape;
+use super::timetable::TimetableGrid;
+use super::ITimetableExporter;
+
+/// Confluence のストレージ形式の表
+///
+/// `http::Confluence::publish_child_page` でページの本文として書き込めます。
+#[derive(Default)]
+pub struct ConfluenceTimetableExporter;
+
+impl ITimetableExporter for ConfluenceTimetableExporter {
+    fn export(&self, schedule: &Schedule) -> String {
+        let grid = TimetableGrid::new(schedule);
+
+        let mut storage = String::new();
+        storage.push_str("<table><tbody>");
+
+        storage.push_str("<tr>");
+        for cell in &grid.header {
+            storage.push_str(&format!("<th>{}</th>", escape(cell)));
+        }
+        storage.push_str("</tr>");
+
+        for row in &grid.rows {
+            storage.push_str("<tr>");
+            for (index, cell) in row.iter().enumerate() {
+                // 先頭列は時間帯の見出し
+                let tag = if index == 0 { "th" } else { "td" };
+                storage.push_str(&format!("<{tag}>{}</{tag}>", escape(cell)));
+            }
+            storage.push_str("</tr>");
+        }
+
+        storage.push_str("</tbody></table>");
+        storage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::timetable::tests::create_schedule;
+    use crate::export::ITimetableExporter;
+
+    use super::ConfluenceTimetableExporter;
+
+    #[test]
+    fn simple() {
+        let storage = ConfluenceTimetableExporter.export(&create_schedule(true));
+        assert_eq!(
+            storage,
+            "<table><tbody>\
+             <tr><th>時間</th><th>Studio A</th><th>Room2</th></tr>\
+             <tr><th>10:00-11:00</th><td>band_a</td><td>band_b</td></tr>\
+             <tr><th>11:00-12:00</th><td></td><td>-</td></tr>\
+             </tbody></table>"
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use crate::algorithm::Schedule;
+
+use super::timetable::TimetableGrid;
+use super::ITimetableExporter;
+
+/// 表計算ソフトに貼り付ける CSV
+#[derive(Default)]
+pub struct CsvTimetableExporter;
+
+impl ITimetableExporter for CsvTimetableExporter {
+    fn export(&self, schedule: &Schedule) -> String {
+        let grid = TimetableGrid::new(schedule);
+
+        let mut writer = ::csv::Writer::from_writer(Vec::default());
+        for record in std::iter::once(&grid.header).chain(&grid.rows) {
+            // メモリへの書き込みは失敗しない
+            writer.write_record(record).unwrap();
+        }
+        String::from_utf8(writer.into_inner().unwrap()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::timetable::tests::create_schedule;
+    use crate::export::ITimetableExporter;
+
+    use super::CsvTimetableExporter;
+
+    #[test]
+    fn simple() {
+        let csv = CsvTimetableExporter.export(&create_schedule(true));
+        assert_eq!(
+            csv,
+            "時間,Studio A,Room2\n10:00-11:00,band_a,band_b\n11:00-12:00,,-\n"
+        );
+    }
+}
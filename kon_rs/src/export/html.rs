@@ -0,0 +1,114 @@
+use crate::algorithm::Schedule;
+
+use super::timetable::TimetableGrid;
+use super::ITimetableExporter;
+
+/// ブラウザでそのまま開ける HTML
+pub struct HtmlTimetableExporter {
+    // ページのタイトル
+    title: String,
+}
+
+impl HtmlTimetableExporter {
+    pub fn new() -> Self {
+        Self {
+            title: "練習スケジュール".to_string(),
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+}
+
+impl Default for HtmlTimetableExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ITimetableExporter for HtmlTimetableExporter {
+    fn export(&self, schedule: &Schedule) -> String {
+        let grid = TimetableGrid::new(schedule);
+        let title = escape(&self.title);
+
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n");
+        html.push_str("<html lang=\"ja\">\n");
+        html.push_str("<head>\n");
+        html.push_str("<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>{title}</title>\n"));
+        html.push_str("<style>\n");
+        html.push_str("table { border-collapse: collapse; }\n");
+        html.push_str("th, td { border: 1px solid #999; padding: 4px 8px; }\n");
+        html.push_str("</style>\n");
+        html.push_str("</head>\n");
+        html.push_str("<body>\n");
+        html.push_str(&format!("<h1>{title}</h1>\n"));
+        html.push_str("<table>\n");
+
+        html.push_str("<thead>\n<tr>");
+        for cell in &grid.header {
+            html.push_str(&format!("<th>{}</th>", escape(cell)));
+        }
+        html.push_str("</tr>\n</thead>\n");
+
+        html.push_str("<tbody>\n");
+        for row in &grid.rows {
+            html.push_str("<tr>");
+            for (index, cell) in row.iter().enumerate() {
+                // 先頭列は時間帯の見出し
+                let tag = if index == 0 { "th" } else { "td" };
+                html.push_str(&format!("<{tag}>{}</{tag}>", escape(cell)));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</tbody>\n");
+
+        html.push_str("</table>\n");
+        html.push_str("</body>\n");
+        html.push_str("</html>\n");
+        html
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::timetable::tests::create_schedule;
+    use crate::export::ITimetableExporter;
+
+    use super::{escape, HtmlTimetableExporter};
+
+    #[test]
+    fn simple() {
+        let html = HtmlTimetableExporter::new()
+            .with_title("第1回 <練習会>")
+            .export(&create_schedule(true));
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.contains("<title>第1回 &lt;練習会&gt;</title>"));
+        assert!(html.contains("<tr><th>時間</th><th>Studio A</th><th>Room2</th></tr>"));
+        assert!(html.contains("<tr><th>10:00-11:00</th><td>band_a</td><td>band_b</td></tr>"));
+        assert!(html.contains("<tr><th>11:00-12:00</th><td></td><td>-</td></tr>"));
+    }
+
+    #[test]
+    fn escape_text() {
+        assert_eq!(escape("a&b<c>\"d'"), "a&amp;b&lt;c&gt;&quot;d&#39;");
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::algorithm::{LocalDateTime, Schedule, ScheduleEntry};
+use crate::BandId;
+
+// RFC 5545 で推奨される 1 行の最大オクテット数
+const LINE_LENGTH_MAX: usize = 75;
+
+/// スケジュールを iCalendar (.ics) 形式で出力します
+///
+/// 時刻を指定していない時間帯 (RoomMatrixBuilder::push_span_time) の枠は予定にできないので出力しません。
+pub struct ICalendarExporter<'a> {
+    schedule: &'a Schedule,
+
+    // カレンダーの名前 (X-WR-CALNAME)
+    calendar_name: String,
+
+    // 出力日時 (DTSTAMP, UTC)
+    timestamp: LocalDateTime,
+}
+
+impl<'a> ICalendarExporter<'a> {
+    pub fn new(schedule: &'a Schedule) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .and_then(|duration| i64::try_from(duration.as_secs()).ok())
+            .and_then(LocalDateTime::from_unix_time)
+            .unwrap_or(LocalDateTime::new(1970, 1, 1, 0, 0));
+        Self {
+            schedule,
+            calendar_name: "練習スケジュール".to_string(),
+            timestamp,
+        }
+    }
+
+    pub fn with_calendar_name(mut self, calendar_name: &str) -> Self {
+        self.calendar_name = calendar_name.to_string();
+        self
+    }
+
+    /// 出力日時を UTC で指定します。既定では現在時刻です
+    pub fn with_timestamp(mut self, timestamp: LocalDateTime) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// 全バンドの予定をまとめたカレンダー
+    pub fn export_all(&self) -> String {
+        let entries: Vec<ScheduleEntry> = self
+            .schedule
+            .entries()
+            .filter(|entry| entry.band_id.is_some())
+            .collect();
+        self.export_entries(&self.calendar_name, &entries)
+    }
+
+    /// バンドの予定だけのカレンダー
+    pub fn export_band(&self, band_id: BandId) -> String {
+        let entries: Vec<ScheduleEntry> = self.schedule.band_entry(band_id).into_iter().collect();
+        let name = self.schedule.band_name(band_id).unwrap_or_default();
+        self.export_entries(&format!("{} ({})", self.calendar_name, name), &entries)
+    }
+
+    /// メンバーが参加する全バンドの予定をまとめたカレンダー
+    pub fn export_member(&self, identifier: &str) -> String {
+        let entries = self.schedule.member(identifier);
+        self.export_entries(
+            &format!("{} ({})", self.calendar_name, identifier),
+            &entries,
+        )
+    }
+
+    /// メンバーの識別子 -> メンバーのカレンダー
+    pub fn export_members(&self) -> HashMap<String, String> {
+        self.schedule
+            .members()
+            .into_iter()
+            .map(|identifier| (identifier.to_string(), self.export_member(identifier)))
+            .collect()
+    }
+
+    fn export_entries(&self, calendar_name: &str, entries: &[ScheduleEntry]) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//kon-rs//schedule//JA".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+            format!("X-WR-CALNAME:{}", escape(calendar_name)),
+        ];
+
+        for entry in entries {
+            let Some(band_id) = entry.band_id else {
+                continue;
+            };
+            let Some(span_time) = self.schedule.span_time(entry.span_index) else {
+                continue;
+            };
+
+            let band_name = self.schedule.band_name(band_id).unwrap_or_default();
+            let room_name = match self.schedule.room_name(entry.room_index) {
+                Some(name) => name.to_string(),
+                None => format!("Room{}", entry.room_index + 1),
+            };
+            let members = self.schedule.band_members(band_id).join(", ");
+
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                // 同じ枠はどのカレンダーでも同じ予定として扱われるようにする
+                format!(
+                    "UID:{}-{}-{}@kon-rs",
+                    span_time.start.to_ical(),
+                    entry.span_index,
+                    entry.room_index
+                ),
+                format!("DTSTAMP:{}Z", self.timestamp.to_ical()),
+                format!("DTSTART:{}", span_time.start.to_ical()),
+                format!("DTEND:{}", span_time.end.to_ical()),
+                format!("SUMMARY:{}", escape(band_name)),
+                format!("LOCATION:{}", escape(&room_name)),
+                format!("DESCRIPTION:{}", escape(&members)),
+                "END:VEVENT".to_string(),
+            ]);
+        }
+
+        lines.push("END:VCALENDAR".to_string());
+
+        lines
+            .iter()
+            .map(|line| fold(line))
+            .collect::<Vec<String>>()
+            .join("\r\n")
+            + "\r\n"
+    }
+}
+
+// TEXT 型の値に使えない文字をエスケープ
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// 長い行を 75 オクテットごとに折り返す。マルチバイト文字の途中では折り返さない
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if LINE_LENGTH_MAX < length + c.len_utf8() {
+            folded.push_str("\r\n ");
+            // 行頭の空白も 1 オクテットとして数える
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::algorithm::{create_live_info, LocalDateTime, RoomMatrix, Schedule, SpanTime};
+
+    use super::{fold, ICalendarExporter};
+
+    fn create_schedule() -> Schedule {
+        // Studio A | Studio B
+        // band_a   | band_b
+        // band_c   | ×
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string(), "x".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+            ("band_c".to_string(), vec!["c".to_string(), "x".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 2]))
+            .collect();
+        let room_matrix = RoomMatrix::builder()
+            .push_named_room("Studio A", 2)
+            .push_named_room("Studio B", 1)
+            .push_span_time(SpanTime::new(
+                LocalDateTime::new(2024, 4, 1, 10, 0),
+                LocalDateTime::new(2024, 4, 1, 11, 0),
+            ))
+            .push_span_time(SpanTime::new(
+                LocalDateTime::new(2024, 4, 1, 11, 0),
+                LocalDateTime::new(2024, 4, 1, 12, 0),
+            ))
+            .build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let band_ids = live_info.band_ids();
+        let studio_a: Vec<_> = room_matrix
+            .iter_room_blocks(room_matrix.rooms()[0])
+            .copied()
+            .collect();
+        let studio_b: Vec<_> = room_matrix
+            .iter_room_blocks(room_matrix.rooms()[1])
+            .copied()
+            .collect();
+        let table = HashMap::from([
+            (studio_a[0], band_ids[0]),
+            (studio_b[0], band_ids[1]),
+            (studio_a[1], band_ids[2]),
+        ]);
+        Schedule::new(table, &room_matrix, &live_info)
+    }
+
+    #[test]
+    fn member() {
+        let schedule = create_schedule();
+        let exporter =
+            ICalendarExporter::new(&schedule).with_timestamp(LocalDateTime::new(2024, 3, 1, 0, 0));
+
+        let ics = exporter.export_member("x");
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("DTSTAMP:20240301T000000Z\r\n"));
+        assert!(ics.contains("DTSTART:20240401T100000\r\nDTEND:20240401T110000\r\n"));
+        assert!(ics.contains("SUMMARY:band_a\r\nLOCATION:Studio A\r\nDESCRIPTION:a\\, x\r\n"));
+        assert!(ics.contains("SUMMARY:band_c\r\nLOCATION:Studio A\r\n"));
+
+        let ics = exporter.export_member("b");
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("LOCATION:Studio B\r\n"));
+
+        let calendars = exporter.export_members();
+        assert_eq!(calendars.len(), 4);
+        assert_eq!(calendars["x"], exporter.export_member("x"));
+    }
+
+    #[test]
+    fn all_and_band() {
+        let schedule = create_schedule();
+        let exporter = ICalendarExporter::new(&schedule).with_calendar_name("第1回");
+
+        let ics = exporter.export_all();
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 3);
+        assert!(ics.contains("X-WR-CALNAME:第1回\r\n"));
+
+        let ics = exporter.export_band(schedule.band_ids()[1]);
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("SUMMARY:band_b\r\n"));
+    }
+
+    #[test]
+    fn fold_line() {
+        let line = "あ".repeat(30);
+        let folded = fold(&line);
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() <= 75));
+        assert_eq!(lines.concat().replace(' ', ""), line);
+    }
+}
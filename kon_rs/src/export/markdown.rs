@@ -0,0 +1,52 @@
+use crate::algorithm::Schedule;
+
+use super::timetable::TimetableGrid;
+use super::ITimetableExporter;
+
+/// LINE や Confluence に貼り付ける Markdown の表
+#[derive(Default)]
+pub struct MarkdownTimetableExporter;
+
+impl ITimetableExporter for MarkdownTimetableExporter {
+    fn export(&self, schedule: &Schedule) -> String {
+        let grid = TimetableGrid::new(schedule);
+
+        let mut lines = vec![
+            format_row(&grid.header),
+            format_row(&vec!["---".to_string(); grid.header.len()]),
+        ];
+        lines.extend(grid.rows.iter().map(|row| format_row(row)));
+
+        lines.join("\n") + "\n"
+    }
+}
+
+fn format_row(cells: &[String]) -> String {
+    let cells: Vec<String> = cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+    format!("| {} |", cells.join(" | "))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::timetable::tests::create_schedule;
+    use crate::export::ITimetableExporter;
+
+    use super::{format_row, MarkdownTimetableExporter};
+
+    #[test]
+    fn simple() {
+        let markdown = MarkdownTimetableExporter.export(&create_schedule(true));
+        assert_eq!(
+            markdown,
+            "| 時間 | Studio A | Room2 |\n\
+             | --- | --- | --- |\n\
+             | 10:00-11:00 | band_a | band_b |\n\
+             | 11:00-12:00 |  | - |\n"
+        );
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(format_row(&["a|b".to_string()]), "| a\\|b |");
+    }
+}
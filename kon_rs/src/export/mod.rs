@@ -0,0 +1,13 @@
+mod confluence;
+mod csv;
+mod html;
+mod ical;
+mod markdown;
+mod timetable;
+
+pub use self::csv::CsvTimetableExporter;
+pub use confluence::ConfluenceTimetableExporter;
+pub use html::HtmlTimetableExporter;
+pub use ical::ICalendarExporter;
+pub use markdown::MarkdownTimetableExporter;
+pub use timetable::ITimetableExporter;
@@ -0,0 +1,136 @@
+use crate::algorithm::Schedule;
+
+/// スケジュールを時間帯 × 部屋の表として文字列に出力します
+pub trait ITimetableExporter {
+    fn export(&self, schedule: &Schedule) -> String;
+}
+
+// 部屋に枠がない時間帯に表示する文字列
+const NO_BLOCK: &str = "-";
+
+/// 時間帯 × 部屋の表
+///
+/// 各出力形式で共通の見出しとセルの文字列を作ります。
+pub(crate) struct TimetableGrid {
+    /// 見出し行 (先頭は時間帯の列)
+    pub header: Vec<String>,
+
+    /// 時間帯ごとの行 (先頭は時間帯のラベル)
+    pub rows: Vec<Vec<String>>,
+}
+
+impl TimetableGrid {
+    pub fn new(schedule: &Schedule) -> Self {
+        let header = std::iter::once("時間".to_string())
+            .chain((0..schedule.room_count()).map(
+                |room_index| match schedule.room_name(room_index) {
+                    Some(name) => name.to_string(),
+                    None => format!("Room{}", room_index + 1),
+                },
+            ))
+            .collect();
+
+        let rows = (0..schedule.span_count())
+            .map(|span_index| {
+                // 時刻がなければ何番目の時間帯かを表示
+                let label = match schedule.span_time(span_index) {
+                    Some(span_time) => span_time.to_time_string(),
+                    None => format!("{}", span_index + 1),
+                };
+
+                let cells = (0..schedule.room_count()).map(|room_index| {
+                    let Some(entry) = schedule.entry(span_index, room_index) else {
+                        return NO_BLOCK.to_string();
+                    };
+
+                    // 空き枠は空欄
+                    entry
+                        .band_id
+                        .and_then(|band_id| schedule.band_name(band_id))
+                        .unwrap_or_default()
+                        .to_string()
+                });
+
+                std::iter::once(label).chain(cells).collect()
+            })
+            .collect();
+
+        Self { header, rows }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::HashMap;
+
+    use crate::algorithm::{create_live_info, LocalDateTime, RoomMatrix, Schedule, SpanTime};
+
+    use super::TimetableGrid;
+
+    // Studio A | Room2
+    // band_a   | band_b
+    // (空き)   | ×
+    pub fn create_schedule(with_time: bool) -> Schedule {
+        let band_table = HashMap::from([
+            ("band_a".to_string(), vec!["a".to_string()]),
+            ("band_b".to_string(), vec!["b".to_string()]),
+        ]);
+        let band_schedule: HashMap<String, Vec<bool>> = band_table
+            .keys()
+            .map(|key| (key.to_string(), vec![true; 2]))
+            .collect();
+        let mut builder = RoomMatrix::builder()
+            .push_named_room("Studio A", 2)
+            .push_room(1);
+        if with_time {
+            builder = builder
+                .push_span_time(SpanTime::new(
+                    LocalDateTime::new(2024, 4, 1, 10, 0),
+                    LocalDateTime::new(2024, 4, 1, 11, 0),
+                ))
+                .push_span_time(SpanTime::new(
+                    LocalDateTime::new(2024, 4, 1, 11, 0),
+                    LocalDateTime::new(2024, 4, 1, 12, 0),
+                ));
+        }
+        let room_matrix = builder.build();
+        let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+        let band_ids = live_info.band_ids();
+        let table = HashMap::from([
+            (
+                *room_matrix
+                    .iter_room_blocks(room_matrix.rooms()[0])
+                    .next()
+                    .unwrap(),
+                band_ids[0],
+            ),
+            (
+                *room_matrix
+                    .iter_room_blocks(room_matrix.rooms()[1])
+                    .next()
+                    .unwrap(),
+                band_ids[1],
+            ),
+        ]);
+        Schedule::new(table, &room_matrix, &live_info)
+    }
+
+    #[test]
+    fn grid() {
+        let grid = TimetableGrid::new(&create_schedule(true));
+        assert_eq!(grid.header, vec!["時間", "Studio A", "Room2"]);
+        assert_eq!(
+            grid.rows,
+            vec![
+                vec!["10:00-11:00", "band_a", "band_b"],
+                vec!["11:00-12:00", "", "-"],
+            ]
+        );
+
+        // 時刻がなければ時間帯の番号
+        let grid = TimetableGrid::new(&create_schedule(false));
+        assert_eq!(grid.rows[0][0], "1");
+        assert_eq!(grid.rows[1][0], "2");
+    }
+}
@@ -1,14 +1,36 @@
 use url::Url;
 
-use super::{rest_type::HierarchyType, ContenType, Content, User, UserType};
+use super::{
+    rest_type::{
+        AncestorType, HierarchyType, PageListType, PageRequestType, PageType, SpaceContentType,
+        SpaceType, StorageBodyType, StorageType, VersionType,
+    },
+    ConfluenceError, ContenType, Content, Page, User, UserType,
+};
+
+// 子ページを一度に取得する数
+const CHILD_PAGE_LIMIT: usize = 100;
 
 pub struct Confluence {
     base_url: Url,
+    client: reqwest::Client,
+
+    // 認証に使うパーソナルアクセストークン
+    token: Option<String>,
 }
 
 impl Confluence {
     pub fn new(base_url: Url) -> Self {
-        Self { base_url }
+        Self {
+            base_url,
+            client: reqwest::Client::new(),
+            token: None,
+        }
+    }
+
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
     }
 
     pub async fn fetch_user<T>(&self, id: T) -> User
@@ -19,7 +41,9 @@ impl Confluence {
         rest_url.set_path(&format!("{}/rest/api/user", self.base_url.path()));
         rest_url.set_query(Some(&format!("username={}", id.as_ref())));
 
-        let response = reqwest::get(rest_url.as_str())
+        let response = self
+            .request(reqwest::Method::GET, rest_url)
+            .send()
             .await
             .unwrap()
             .text()
@@ -41,7 +65,9 @@ impl Confluence {
         ));
         rest_url.set_query(Some("expand=body.view"));
 
-        let response = reqwest::get(rest_url.as_str())
+        let response = self
+            .request(reqwest::Method::GET, rest_url)
+            .send()
             .await
             .unwrap()
             .text()
@@ -61,7 +87,9 @@ impl Confluence {
         ));
 
         println!("{}", rest_url.as_ref());
-        let response = reqwest::get(rest_url.as_str())
+        let response = self
+            .request(reqwest::Method::GET, rest_url)
+            .send()
             .await
             .unwrap()
             .text()
@@ -74,4 +102,149 @@ impl Confluence {
             println!("{}", result.id);
         }
     }
+
+    /// スケジュールなどを子ページとして書き込みます
+    ///
+    /// 同じタイトルの子ページがあればバージョンを上げて更新し、なければ作成します。
+    /// `storage` は Confluence のストレージ形式の本文です。
+    pub async fn publish_child_page(
+        &self,
+        parent_id: u64,
+        title: &str,
+        storage: &str,
+    ) -> Result<Page, ConfluenceError> {
+        match self.find_child_page(parent_id, title).await? {
+            Some(page) => self.update_page(&page, storage).await,
+            None => self.create_child_page(parent_id, title, storage).await,
+        }
+    }
+
+    /// タイトルが一致する子ページを探します
+    pub async fn find_child_page(
+        &self,
+        parent_id: u64,
+        title: &str,
+    ) -> Result<Option<Page>, ConfluenceError> {
+        let rest_url = self.rest_url(&format!("content/{}/child/page", parent_id));
+
+        // 子ページが多いときは何回かに分けて取得
+        let mut start = 0;
+        loop {
+            let page_list: PageListType = self
+                .request(reqwest::Method::GET, rest_url.clone())
+                .query(&[
+                    ("expand", "version".to_string()),
+                    ("start", start.to_string()),
+                    ("limit", CHILD_PAGE_LIMIT.to_string()),
+                ])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            if let Some(page_type) = page_list.results.iter().find(|x| x.title == title) {
+                return Page::try_from(page_type).map(Some);
+            }
+
+            if page_list.results.len() < CHILD_PAGE_LIMIT {
+                return Ok(None);
+            }
+            start += page_list.results.len();
+        }
+    }
+
+    /// 子ページを作成します
+    pub async fn create_child_page(
+        &self,
+        parent_id: u64,
+        title: &str,
+        storage: &str,
+    ) -> Result<Page, ConfluenceError> {
+        // 親ページと同じスペースに作成する
+        let parent: SpaceContentType = self
+            .request(
+                reqwest::Method::GET,
+                self.rest_url(&format!("content/{}", parent_id)),
+            )
+            .query(&[("expand", "space")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let request = PageRequestType {
+            content_type: "page".to_string(),
+            title: title.to_string(),
+            space: Some(SpaceType {
+                key: parent.space.key,
+            }),
+            ancestors: vec![AncestorType {
+                id: parent_id.to_string(),
+            }],
+            version: None,
+            body: Self::create_storage_body(storage),
+        };
+        let page_type: PageType = self
+            .request(reqwest::Method::POST, self.rest_url("content"))
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Page::try_from(&page_type)
+    }
+
+    /// ページの本文を置き換えます
+    ///
+    /// 取得してから他の人が更新していた場合はサーバーが 409 Conflict を返すのでエラーになります。
+    pub async fn update_page(&self, page: &Page, storage: &str) -> Result<Page, ConfluenceError> {
+        let request = PageRequestType {
+            content_type: "page".to_string(),
+            title: page.title().to_string(),
+            space: None,
+            ancestors: Vec::new(),
+            version: Some(VersionType {
+                number: page.version() + 1,
+            }),
+            body: Self::create_storage_body(storage),
+        };
+        let page_type: PageType = self
+            .request(
+                reqwest::Method::PUT,
+                self.rest_url(&format!("content/{}", page.id())),
+            )
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Page::try_from(&page_type)
+    }
+
+    fn rest_url(&self, path: &str) -> Url {
+        let mut rest_url = self.base_url.clone();
+        rest_url.set_path(&format!("{}/rest/api/{}", self.base_url.path(), path));
+        rest_url
+    }
+
+    fn request(&self, method: reqwest::Method, url: Url) -> reqwest::RequestBuilder {
+        let builder = self.client.request(method, url);
+        match &self.token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
+    fn create_storage_body(storage: &str) -> StorageBodyType {
+        StorageBodyType {
+            storage: StorageType {
+                value: storage.to_string(),
+                representation: "storage".to_string(),
+            },
+        }
+    }
 }
@@ -0,0 +1,45 @@
+use std::fmt;
+
+/// Confluence とのやりとりに失敗した理由
+#[derive(Debug)]
+pub enum ConfluenceError {
+    /// リクエストが失敗したか、エラーのステータスが返った
+    Request(reqwest::Error),
+
+    /// 返ってきたページの識別子が数値ではない
+    InvalidPageId { id: String },
+}
+
+impl ConfluenceError {
+    /// サーバーが返したエラーのステータス
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            ConfluenceError::Request(error) => error.status(),
+            ConfluenceError::InvalidPageId { .. } => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ConfluenceError {
+    fn from(error: reqwest::Error) -> Self {
+        ConfluenceError::Request(error)
+    }
+}
+
+impl fmt::Display for ConfluenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfluenceError::Request(error) => write!(f, "request failed: {error}"),
+            ConfluenceError::InvalidPageId { id } => write!(f, "invalid page id: {id}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfluenceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfluenceError::Request(error) => Some(error),
+            ConfluenceError::InvalidPageId { .. } => None,
+        }
+    }
+}
@@ -1,8 +1,11 @@
 mod confluence;
 mod detail;
+mod error;
 mod rest_type;
 
 pub use confluence::Confluence;
+pub use error::ConfluenceError;
+use rest_type::PageType;
 pub use rest_type::{BodyType, ContenType, UserType, ViewType};
 
 pub struct User {
@@ -46,3 +49,72 @@ impl Content {
         &self.raw_content
     }
 }
+
+/// 書き込み先のページ
+pub struct Page {
+    id: u64,
+    title: String,
+    version: u32,
+}
+
+impl TryFrom<&PageType> for Page {
+    type Error = ConfluenceError;
+
+    fn try_from(page_type: &PageType) -> Result<Self, Self::Error> {
+        let Ok(id) = page_type.id.parse() else {
+            return Err(ConfluenceError::InvalidPageId {
+                id: page_type.id.clone(),
+            });
+        };
+
+        Ok(Self {
+            id,
+            title: page_type.title.clone(),
+            version: page_type.version.number,
+        })
+    }
+}
+
+impl Page {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// 更新するたびに 1 ずつ増えるページのバージョン
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rest_type::{PageType, VersionType};
+    use super::{ConfluenceError, Page};
+
+    fn create_page_type(id: &str) -> PageType {
+        PageType {
+            id: id.to_string(),
+            title: "時間割".to_string(),
+            version: VersionType { number: 3 },
+        }
+    }
+
+    #[test]
+    fn page_from_page_type() {
+        let page = Page::try_from(&create_page_type("123")).unwrap();
+        assert_eq!(page.id(), 123);
+        assert_eq!(page.title(), "時間割");
+        assert_eq!(page.version(), 3);
+    }
+
+    #[test]
+    fn page_from_invalid_id() {
+        let error = Page::try_from(&create_page_type("abc")).err().unwrap();
+        assert!(matches!(error, ConfluenceError::InvalidPageId { ref id } if id == "abc"));
+        assert_eq!(error.to_string(), "invalid page id: abc");
+    }
+}
@@ -18,6 +18,70 @@ pub struct HierarchyResultType {
     // pub children: Vec<ContenType>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageListType {
+    pub results: Vec<PageType>,
+}
+
+/// 作成・更新したページ
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageType {
+    pub id: String,
+    pub title: String,
+    pub version: VersionType,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpaceContentType {
+    pub space: SpaceType,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpaceType {
+    pub key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionType {
+    pub number: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AncestorType {
+    pub id: String,
+}
+
+/// ページの作成・更新のリクエスト
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageRequestType {
+    #[serde(rename = "type")]
+    pub content_type: String,
+
+    pub title: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub space: Option<SpaceType>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub ancestors: Vec<AncestorType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<VersionType>,
+
+    pub body: StorageBodyType,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StorageBodyType {
+    pub storage: StorageType,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StorageType {
+    pub value: String,
+    pub representation: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChildPageType {
     pub id: String,
@@ -49,6 +113,8 @@ pub struct UserType {
 mod tests {
     use crate::http::ContenType;
 
+    use super::{PageRequestType, StorageBodyType, StorageType, VersionType};
+
     #[test]
     fn deserialize() {
         let data = include_str!("../../res/rest.json");
@@ -57,4 +123,25 @@ mod tests {
         assert_eq!(content.title, "20240406_春ライブ／合同練習会／時間割調整");
         assert_eq!(content.body.view.value, "<div></div>");
     }
+
+    #[test]
+    fn serialize_page_request() {
+        let request = PageRequestType {
+            content_type: "page".to_string(),
+            title: "時間割".to_string(),
+            space: None,
+            ancestors: Vec::new(),
+            version: Some(VersionType { number: 2 }),
+            body: StorageBodyType {
+                storage: StorageType {
+                    value: "<p></p>".to_string(),
+                    representation: "storage".to_string(),
+                },
+            },
+        };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"type":"page","title":"時間割","version":{"number":2},"body":{"storage":{"value":"<p></p>","representation":"storage"}}}"#
+        );
+    }
 }
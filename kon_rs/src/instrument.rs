@@ -0,0 +1,290 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::InstrumentType;
+
+// 楽器と表示名、読み取るときの別名
+// 別名は大文字・小文字と空白、"_"、"-" を区別せずに比較します
+const INSTRUMENT_NAMES: [(InstrumentType, &str, &[&str]); 10] = [
+    (
+        InstrumentType::VOCAL,
+        "Vocal",
+        &["vo", "vocals", "ボーカル", "ヴォーカル", "歌"],
+    ),
+    (
+        InstrumentType::ELECTRIC_GUITAR,
+        "ElectricGuitar",
+        &["guitar", "gt", "エレキギター", "ギター"],
+    ),
+    (
+        InstrumentType::ACOUSTIC_GUITAR,
+        "AcousticGuitar",
+        // "AcounsticGuitar" は以前の画面で使っていた綴り間違いの表示名
+        &["acounsticguitar", "ag", "アコースティックギター", "アコギ"],
+    ),
+    (
+        InstrumentType::ELECTRIC_BASS,
+        "ElectricBass",
+        &["bass", "ba", "ベース", "エレキベース", "エレベ"],
+    ),
+    (
+        InstrumentType::TENOR_SAXPHONE,
+        "TenorSaxophone",
+        &[
+            "tenorsaxphone",
+            "tenorsax",
+            "sax",
+            "テナーサックス",
+            "サックス",
+        ],
+    ),
+    (
+        InstrumentType::KEYBOARD,
+        "Keyboard",
+        &["key", "keys", "kb", "キーボード"],
+    ),
+    (InstrumentType::PIANO, "Piano", &["pf", "ピアノ"]),
+    (
+        InstrumentType::TROMBONE,
+        "Trombone",
+        &["tronbone", "tb", "トロンボーン"],
+    ),
+    (
+        InstrumentType::DRUMS,
+        "Drums",
+        &["drum", "dr", "ドラム", "ドラムス"],
+    ),
+    (InstrumentType::OTHER, "Other", &["その他"]),
+];
+
+// 複数の楽器を並べるときの区切り
+const SEPARATOR: char = '|';
+
+/// 楽器の名前として読み取れない文字列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseInstrumentError {
+    value: String,
+}
+
+impl ParseInstrumentError {
+    /// 読み取れなかった楽器の名前
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl fmt::Display for ParseInstrumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown instrument: {}", self.value)
+    }
+}
+
+impl std::error::Error for ParseInstrumentError {}
+
+/// 読み取った楽器
+#[derive(Debug, Default, Clone)]
+pub struct ParsedInstrument {
+    pub flags: InstrumentType,
+
+    /// "Other:ウクレレ" のように書いた一覧にない楽器の名前
+    pub custom: Vec<String>,
+}
+
+/// 楽器の名前を読み取ります
+///
+/// "Vocal|Drums" のように "|" で区切ると複数の楽器を読み取ります。
+/// 一覧にない楽器は "Other:ウクレレ" のように書くと InstrumentType::OTHER として読み取り、名前を custom に残します。
+pub fn parse_instruments(s: &str) -> Result<ParsedInstrument, ParseInstrumentError> {
+    let mut parsed = ParsedInstrument::default();
+    for value in s.split(SEPARATOR).map(str::trim) {
+        if value.is_empty() {
+            continue;
+        }
+
+        let Some((instrument, custom)) = parse_instrument(value) else {
+            return Err(ParseInstrumentError {
+                value: value.to_string(),
+            });
+        };
+        parsed.flags |= instrument;
+        if let Some(custom) = custom {
+            parsed.custom.push(custom.to_string());
+        }
+    }
+    Ok(parsed)
+}
+
+/// 楽器の名前を読み取ります
+///
+/// 書式は parse_instruments と同じです。一覧にない楽器の名前は捨てます。
+impl FromStr for InstrumentType {
+    type Err = ParseInstrumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_instruments(s).map(|parsed| parsed.flags)
+    }
+}
+
+/// "Vocal|Drums" のように "|" で区切った楽器の名前
+impl fmt::Display for InstrumentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = INSTRUMENT_NAMES
+            .iter()
+            .filter(|(instrument, _, _)| self.contains(*instrument))
+            .map(|(_, name, _)| *name)
+            .collect();
+        write!(f, "{}", names.join(&SEPARATOR.to_string()))
+    }
+}
+
+impl Serialize for InstrumentType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for InstrumentType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+// 楽器と、"Other:ウクレレ" の楽器名
+fn parse_instrument(value: &str) -> Option<(InstrumentType, Option<&str>)> {
+    let (value, custom) = match value.split_once([':', '：']) {
+        Some((prefix, custom)) => (prefix, Some(custom.trim())),
+        None => (value, None),
+    };
+
+    let normalized = normalize(value);
+    let instrument = INSTRUMENT_NAMES
+        .iter()
+        .find(|(_, name, aliases)| {
+            normalize(name) == normalized || aliases.iter().any(|x| normalize(x) == normalized)
+        })
+        .map(|(instrument, _, _)| *instrument)?;
+
+    // 楽器名を書けるのは Other だけ
+    match custom {
+        Some(_) if !InstrumentType::OTHER.contains(instrument) => None,
+        Some(custom) if !custom.is_empty() => Some((instrument, Some(custom))),
+        _ => Some((instrument, None)),
+    }
+}
+
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|x| !x.is_whitespace() && *x != '_' && *x != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::InstrumentType;
+
+    use super::parse_instruments;
+
+    #[test]
+    fn parse() {
+        let parse = |x: &str| x.parse::<InstrumentType>().unwrap().bits();
+        assert_eq!(parse("Vocal"), InstrumentType::VOCAL.bits());
+        assert_eq!(
+            parse("electric_guitar"),
+            InstrumentType::ELECTRIC_GUITAR.bits()
+        );
+        assert_eq!(
+            parse("Acoustic Guitar"),
+            InstrumentType::ACOUSTIC_GUITAR.bits()
+        );
+        assert_eq!(
+            parse("acousticguitar"),
+            InstrumentType::ACOUSTIC_GUITAR.bits()
+        );
+        assert_eq!(parse("ドラム"), InstrumentType::DRUMS.bits());
+        assert_eq!(parse("ピアノ"), InstrumentType::PIANO.bits());
+
+        // よくある綴り間違い
+        assert_eq!(parse("Tronbone"), InstrumentType::TROMBONE.bits());
+        assert_eq!(
+            parse("AcounsticGuitar"),
+            InstrumentType::ACOUSTIC_GUITAR.bits()
+        );
+        assert_eq!(
+            parse("TenorSaxphone"),
+            InstrumentType::TENOR_SAXPHONE.bits()
+        );
+
+        // 複数の楽器
+        assert_eq!(
+            parse("Vocal | ElectricBass"),
+            (InstrumentType::VOCAL | InstrumentType::ELECTRIC_BASS).bits()
+        );
+        assert_eq!(parse(""), 0);
+
+        // 一覧にない楽器
+        assert_eq!(parse("Other:ウクレレ"), InstrumentType::OTHER.bits());
+        assert_eq!(parse("その他"), InstrumentType::OTHER.bits());
+    }
+
+    #[test]
+    fn parse_unknown() {
+        let error = "Vocal|Ukulele".parse::<InstrumentType>().unwrap_err();
+        assert_eq!(error.value(), "Ukulele");
+        assert_eq!(error.to_string(), "unknown instrument: Ukulele");
+
+        // Other 以外には楽器名を書けない
+        let error = "Vocal:typo".parse::<InstrumentType>().unwrap_err();
+        assert_eq!(error.value(), "Vocal:typo");
+    }
+
+    #[test]
+    fn parse_custom() {
+        let parsed = parse_instruments("Vocal | Other:ウクレレ | その他：三味線").unwrap();
+        assert_eq!(
+            parsed.flags.bits(),
+            (InstrumentType::VOCAL | InstrumentType::OTHER).bits()
+        );
+        assert_eq!(parsed.custom, vec!["ウクレレ", "三味線"]);
+
+        let parsed = parse_instruments("Other:").unwrap();
+        assert_eq!(parsed.flags.bits(), InstrumentType::OTHER.bits());
+        assert!(parsed.custom.is_empty());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(InstrumentType::TROMBONE.to_string(), "Trombone");
+        assert_eq!(
+            (InstrumentType::DRUMS | InstrumentType::VOCAL).to_string(),
+            "Vocal|Drums"
+        );
+        assert_eq!(InstrumentType::empty().to_string(), "");
+
+        // 表示名から元に戻る
+        let instruments = InstrumentType::all();
+        assert_eq!(
+            instruments
+                .to_string()
+                .parse::<InstrumentType>()
+                .unwrap()
+                .bits(),
+            instruments.bits()
+        );
+    }
+
+    #[test]
+    fn serde() {
+        let instruments = InstrumentType::VOCAL | InstrumentType::KEYBOARD;
+        let json = serde_json::to_string(&instruments).unwrap();
+        assert_eq!(json, "\"Vocal|Keyboard\"");
+
+        let value: InstrumentType = serde_json::from_str("\"ボーカル|キーボード\"").unwrap();
+        assert_eq!(value.bits(), instruments.bits());
+        assert!(serde_json::from_str::<InstrumentType>("\"Ukulele\"").is_err());
+    }
+}
@@ -1,8 +1,13 @@
+use algorithm::{ConcertSetting, ConcertTimetable, RehearsalSetting, RehearsalTimetable};
 use uuid::Uuid;
 
 pub mod algorithm;
 pub mod analyzer;
+pub mod export;
 pub mod http;
+mod instrument;
+
+pub use instrument::{parse_instruments, ParseInstrumentError, ParsedInstrument};
 
 bitflags::bitflags! {
     #[derive(Clone, Copy, Debug, Default)]
@@ -16,6 +21,9 @@ bitflags::bitflags! {
         const PIANO           = 0b000001000000;
         const TROMBONE        = 0b000010000000;
         const DRUMS           = 0b000100000000;
+
+        // 一覧にない楽器
+        const OTHER           = 0b001000000000;
     }
 }
 
@@ -89,9 +97,51 @@ impl UserId {
     }
 }
 
+/// バンドでのメンバーの担当
+///
+/// User の instrument_type は演奏できる楽器すべてですが、こちらはこのバンドで担当する楽器です。
+#[derive(Debug, Clone)]
+pub struct BandMember {
+    pub id: String,
+    pub instrument: InstrumentType,
+}
+
+impl BandMember {
+    pub fn new(id: &str, instrument: InstrumentType) -> Self {
+        Self {
+            id: id.to_string(),
+            instrument,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Band {
-    pub member_ids: Vec<String>,
+    pub name: String,
+    pub members: Vec<BandMember>,
+}
+
+impl Band {
+    pub fn member_ids(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(|member| member.id.as_str())
+    }
+
+    /// メンバーの担当楽器をまとめた編成
+    pub fn instruments(&self) -> InstrumentType {
+        self.members
+            .iter()
+            .fold(InstrumentType::empty(), |instruments, member| {
+                instruments | member.instrument
+            })
+    }
+
+    /// メンバーがこのバンドで担当する楽器
+    pub fn member_instrument(&self, id: &str) -> Option<InstrumentType> {
+        self.members
+            .iter()
+            .find(|member| member.id == id)
+            .map(|member| member.instrument)
+    }
 }
 
 #[derive(Clone)]
@@ -107,3 +157,15 @@ pub struct LiveProgram {
     // 出演バンド
     pub item: Vec<String>,
 }
+
+impl LiveProgram {
+    /// 出演バンドの順に開始・終了時刻を割り当てます
+    pub fn create_timetable(&self, setting: &ConcertSetting) -> ConcertTimetable {
+        ConcertTimetable::new(self, setting)
+    }
+
+    /// 出演と逆順にリハーサルの時間を割り当てます
+    pub fn create_rehearsal_timetable(&self, setting: &RehearsalSetting) -> RehearsalTimetable {
+        RehearsalTimetable::new(self, setting)
+    }
+}
@@ -0,0 +1,240 @@
+use std::sync::{Arc, Mutex};
+
+use kon_rs::http::Confluence;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use url::Url;
+
+// 親ページの ID
+const PARENT_ID: u64 = 100;
+
+struct MockPage {
+    id: u64,
+    title: String,
+    version: u32,
+    body: String,
+}
+
+// Confluence の REST API のうち、ページの書き込みに使うものだけを真似るサーバー
+#[derive(Default)]
+struct MockConfluence {
+    pages: Vec<MockPage>,
+
+    // 受け取ったリクエストのメソッドとパス
+    requests: Vec<String>,
+
+    // 受け取った Authorization ヘッダー
+    authorizations: Vec<String>,
+}
+
+impl MockConfluence {
+    fn handle(&mut self, method: &str, target: &str, body: &str) -> (u16, Value) {
+        let path = target.split('?').next().unwrap();
+        self.requests.push(format!("{} {}", method, path));
+
+        let segments: Vec<&str> = path
+            .trim_start_matches("/confluence/rest/api/")
+            .split('/')
+            .collect();
+        match (method, segments.as_slice()) {
+            ("GET", ["content", id, "child", "page"]) => {
+                assert_eq!(id.parse::<u64>().unwrap(), PARENT_ID);
+                let results: Vec<Value> = self.pages.iter().map(Self::to_json).collect();
+                (200, json!({ "results": results }))
+            }
+            ("GET", ["content", id]) => (
+                200,
+                json!({ "id": id.to_string(), "title": "親ページ", "space": { "key": "KON" } }),
+            ),
+            ("POST", ["content"]) => {
+                let request: Value = serde_json::from_str(body).unwrap();
+                assert_eq!(request["type"], "page");
+                assert_eq!(request["space"]["key"], "KON");
+                assert_eq!(request["ancestors"][0]["id"], PARENT_ID.to_string());
+                assert_eq!(request["body"]["storage"]["representation"], "storage");
+
+                let page = MockPage {
+                    id: 200 + self.pages.len() as u64,
+                    title: request["title"].as_str().unwrap().to_string(),
+                    version: 1,
+                    body: request["body"]["storage"]["value"]
+                        .as_str()
+                        .unwrap()
+                        .to_string(),
+                };
+                let response = Self::to_json(&page);
+                self.pages.push(page);
+                (200, response)
+            }
+            ("PUT", ["content", id]) => {
+                let request: Value = serde_json::from_str(body).unwrap();
+                let id: u64 = id.parse().unwrap();
+                let Some(page) = self.pages.iter_mut().find(|x| x.id == id) else {
+                    return (404, json!({}));
+                };
+
+                // バージョンは現在の次でなければいけない
+                let version = request["version"]["number"].as_u64().unwrap() as u32;
+                if version != page.version + 1 {
+                    return (409, json!({ "message": "version conflict" }));
+                }
+
+                page.version = version;
+                page.body = request["body"]["storage"]["value"]
+                    .as_str()
+                    .unwrap()
+                    .to_string();
+                (200, Self::to_json(page))
+            }
+            _ => (404, json!({})),
+        }
+    }
+
+    fn to_json(page: &MockPage) -> Value {
+        json!({
+            "id": page.id.to_string(),
+            "title": page.title,
+            "version": { "number": page.version },
+        })
+    }
+}
+
+async fn serve(mut stream: TcpStream, mock: Arc<Mutex<MockConfluence>>) {
+    // ヘッダーの終わりまで読む
+    let mut buffer = Vec::new();
+    let header_end = loop {
+        let mut chunk = [0u8; 1024];
+        let size = stream.read(&mut chunk).await.unwrap();
+        if size == 0 {
+            return;
+        }
+        buffer.extend_from_slice(&chunk[..size]);
+        if let Some(position) = buffer.windows(4).position(|x| x == b"\r\n\r\n") {
+            break position + 4;
+        }
+    };
+
+    let header = String::from_utf8(buffer[..header_end].to_vec()).unwrap();
+    let mut lines = header.lines();
+    let mut request_line = lines.next().unwrap().split(' ');
+    let method = request_line.next().unwrap().to_string();
+    let target = request_line.next().unwrap().to_string();
+
+    let mut content_length = 0;
+    let mut authorization = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.trim().parse().unwrap(),
+            "authorization" => authorization = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+
+    // 本文を読む
+    while buffer.len() < header_end + content_length {
+        let mut chunk = [0u8; 1024];
+        let size = stream.read(&mut chunk).await.unwrap();
+        buffer.extend_from_slice(&chunk[..size]);
+    }
+    let body = String::from_utf8(buffer[header_end..].to_vec()).unwrap();
+
+    let (status, response) = {
+        let mut mock = mock.lock().unwrap();
+        if let Some(authorization) = authorization {
+            mock.authorizations.push(authorization);
+        }
+        mock.handle(&method, &target, &body)
+    };
+
+    let response = response.to_string();
+    let message = format!(
+        "HTTP/1.1 {} MOCK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        response.len(),
+        response
+    );
+    stream.write_all(message.as_bytes()).await.unwrap();
+    stream.shutdown().await.unwrap();
+}
+
+async fn start_mock_server(mock: Arc<Mutex<MockConfluence>>) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(serve(stream, mock.clone()));
+        }
+    });
+
+    Url::parse(&format!("http://{}/confluence", address)).unwrap()
+}
+
+#[tokio::test]
+async fn publish_child_page() {
+    let mock = Arc::new(Mutex::new(MockConfluence::default()));
+    let base_url = start_mock_server(mock.clone()).await;
+    let confluence = Confluence::new(base_url).with_token("secret");
+
+    // 初回は作成
+    let page = confluence
+        .publish_child_page(PARENT_ID, "時間割", "<p>v1</p>")
+        .await
+        .unwrap();
+    assert_eq!(page.id(), 200);
+    assert_eq!(page.title(), "時間割");
+    assert_eq!(page.version(), 1);
+
+    // 2 回目は同じページのバージョンを上げて更新
+    let page = confluence
+        .publish_child_page(PARENT_ID, "時間割", "<p>v2</p>")
+        .await
+        .unwrap();
+    assert_eq!(page.id(), 200);
+    assert_eq!(page.version(), 2);
+
+    let mock = mock.lock().unwrap();
+    assert_eq!(mock.pages.len(), 1);
+    assert_eq!(mock.pages[0].body, "<p>v2</p>");
+    assert_eq!(
+        mock.requests,
+        vec![
+            "GET /confluence/rest/api/content/100/child/page",
+            "GET /confluence/rest/api/content/100",
+            "POST /confluence/rest/api/content",
+            "GET /confluence/rest/api/content/100/child/page",
+            "PUT /confluence/rest/api/content/200",
+        ]
+    );
+    assert!(mock.authorizations.iter().all(|x| x == "Bearer secret"));
+    assert_eq!(mock.authorizations.len(), mock.requests.len());
+}
+
+#[tokio::test]
+async fn version_conflict() {
+    let mock = Arc::new(Mutex::new(MockConfluence::default()));
+    let base_url = start_mock_server(mock.clone()).await;
+    let confluence = Confluence::new(base_url);
+
+    let page = confluence
+        .create_child_page(PARENT_ID, "時間割", "<p>v1</p>")
+        .await
+        .unwrap();
+
+    // 取得した後に他の人が更新した
+    mock.lock().unwrap().pages[0].version = 2;
+
+    let error = confluence
+        .update_page(&page, "<p>v2</p>")
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(error.status(), Some(reqwest::StatusCode::CONFLICT));
+    assert_eq!(mock.lock().unwrap().pages[0].body, "<p>v1</p>");
+}
@@ -1,6 +1,29 @@
 use std::{collections::HashMap, sync::Arc};
 
-use kon_rs::algorithm::{create_live_info, RoomMatrix, Scheduler};
+use kon_rs::algorithm::{
+    create_live_info, BandScheduleConstraint, IConstraint, LiveInfo, MemberConflictConstraint,
+    RoomMatrix, Scheduler, TraverseOperation,
+};
+
+// 先頭の枠に特定のバンドを入れない独自の制約
+struct FirstBlockConstraint {
+    band_index: i32,
+}
+
+impl IConstraint for FirstBlockConstraint {
+    fn invoke(
+        &self,
+        indicies: &[i32],
+        _room_matrix: &RoomMatrix,
+        _live_info: &LiveInfo,
+    ) -> TraverseOperation {
+        if indicies[0] == self.band_index {
+            TraverseOperation::Skip(1)
+        } else {
+            TraverseOperation::Next
+        }
+    }
+}
 
 #[test]
 fn simple() {
@@ -16,10 +39,10 @@ fn simple() {
         .keys()
         .map(|key| (key.to_string(), vec![true; 3]))
         .collect();
-    let live_info = create_live_info(&band_table, &band_schedule, &room_matrix);
+    let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
 
     let scheduler = Scheduler::new();
-    let result = scheduler.assign(&room_matrix, &live_info);
+    let result = scheduler.assign(&room_matrix, &live_info).unwrap();
     assert_eq!(result.len(), 6);
 }
 
@@ -41,10 +64,10 @@ fn simple_parallel() {
         .keys()
         .map(|key| (key.to_string(), vec![true; 3]))
         .collect();
-    let live_info = create_live_info(&band_table, &band_schedule, &room_matrix);
+    let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
 
     let scheduler = Scheduler::new();
-    let result = scheduler.assign(&room_matrix, &live_info);
+    let result = scheduler.assign(&room_matrix, &live_info).unwrap();
     assert_eq!(result.len(), 4);
 }
 
@@ -66,10 +89,10 @@ fn simple_none() {
         .keys()
         .map(|key| (key.to_string(), vec![true; 3]))
         .collect();
-    let live_info = create_live_info(&band_table, &band_schedule, &room_matrix);
+    let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
 
     let scheduler = Scheduler::new();
-    let result = scheduler.assign(&room_matrix, &live_info);
+    let result = scheduler.assign(&room_matrix, &live_info).unwrap();
     assert_eq!(result.len(), 0);
 }
 
@@ -90,11 +113,11 @@ fn simple_() {
         .keys()
         .map(|key| (key.to_string(), vec![true; 5]))
         .collect();
-    let live_info = create_live_info(&band_table, &band_schedule, &room_matrix);
+    let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
     let live_info = Arc::new(live_info);
 
     let scheduler = Scheduler::new();
-    let result = scheduler.assign(&room_matrix, &live_info);
+    let result = scheduler.assign(&room_matrix, &live_info).unwrap();
     assert_eq!(result.len(), 120);
 
     // 並列実行
@@ -104,7 +127,41 @@ fn simple_() {
         .expect("Failed building the Runtime")
         .block_on(async {
             let scheduler = Scheduler::new();
-            let result = scheduler.assign_async(room_matrix, live_info).await;
+            let result = scheduler
+                .assign_async(room_matrix, live_info)
+                .await
+                .unwrap();
             assert_eq!(result.len(), 120);
         });
 }
+
+#[test]
+fn custom_constraint() {
+    // 1 部屋 3 枠
+    // 先頭の枠に band_x が入る 2 通りが除外される
+    let room_matrix = RoomMatrix::builder().push_room(3).build();
+    let band_table = HashMap::from([
+        ("band_x".to_string(), vec!["a".to_string()]),
+        ("band_y".to_string(), vec!["b".to_string()]),
+        ("band_z".to_string(), vec!["c".to_string()]),
+    ]);
+    let band_schedule: HashMap<String, Vec<bool>> = band_table
+        .keys()
+        .map(|key| (key.to_string(), vec![true; 3]))
+        .collect();
+    let live_info = create_live_info(&band_table, &band_schedule, &room_matrix).unwrap();
+
+    let scheduler = Scheduler::builder()
+        .push_constraint(BandScheduleConstraint)
+        .push_constraint(MemberConflictConstraint)
+        .push_constraint(FirstBlockConstraint { band_index: 0 })
+        .build();
+    let result = scheduler.assign(&room_matrix, &live_info).unwrap();
+    assert_eq!(result.len(), 4);
+
+    let band_x = live_info.band_ids()[0];
+    let first_block = room_matrix.blocks()[0];
+    assert!(result
+        .iter()
+        .all(|schedule| schedule.table()[&first_block] != band_x));
+}
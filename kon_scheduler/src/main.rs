@@ -1,12 +1,17 @@
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use indicatif::{ProgressBar, ProgressStyle};
+use kon_players::{check_lineups, parse_roster, MemberList, Strictness};
 use kon_rs::{
     algorithm::{
-        IScheduleCallback, LiveInfo, RoomMatrix, Scheduler, SchedulerInfo, TaskId, TaskInfo,
+        validate_live_info, IScheduleCallback, LiveInfo, RoomMatrix, Schedule, Scheduler,
+        SchedulerInfo, TaskId, TaskInfo,
+    },
+    export::{
+        ConfluenceTimetableExporter, CsvTimetableExporter, HtmlTimetableExporter,
+        ITimetableExporter, MarkdownTimetableExporter,
     },
-    BandId, BlockId,
 };
 use kon_scheduler::{parse_bands, parse_schedule};
 
@@ -26,6 +31,10 @@ struct Args {
     #[arg(short = 'r', long = "rooms")]
     rooms: String,
 
+    /// member list csv to check each band's lineup
+    #[arg(long = "roster")]
+    roster: Option<String>,
+
     #[arg(short = 'd', long = "sub-tree-depth", default_value_t = 8)]
     sub_tree_depth: usize,
 
@@ -35,6 +44,19 @@ struct Args {
     /// make thread count 1 for debug
     #[arg(long, default_value_t = false)]
     force_synchronize_for_debug: bool,
+
+    /// output format of each schedule
+    #[arg(short = 'f', long = "format", value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum OutputFormat {
+    Text,
+    Csv,
+    Markdown,
+    Html,
+    Confluence,
 }
 
 #[derive(Debug, Clone)]
@@ -42,14 +64,16 @@ struct ScheduleCallback {
     progress_bar: Option<ProgressBar>,
     finished_task_count: usize,
     all_task_count: usize,
+    format: OutputFormat,
 }
 
 impl ScheduleCallback {
-    pub fn new() -> Self {
+    pub fn new(format: OutputFormat) -> Self {
         Self {
             progress_bar: None,
             finished_task_count: 0,
             all_task_count: 0,
+            format,
         }
     }
 }
@@ -75,9 +99,9 @@ impl IScheduleCallback for ScheduleCallback {
 
     fn on_assigned(
         &mut self,
-        table: &HashMap<BlockId, BandId>,
-        room_matrix: &RoomMatrix,
-        live_info: &LiveInfo,
+        schedule: &Schedule,
+        _room_matrix: &RoomMatrix,
+        _live_info: &LiveInfo,
     ) {
         let Some(progress_bar) = &self.progress_bar else {
             return;
@@ -85,15 +109,28 @@ impl IScheduleCallback for ScheduleCallback {
 
         progress_bar.println("=============================================\n");
 
-        for span_id in room_matrix.spans() {
-            let mut string = String::new();
-            for block_id in room_matrix.iter_span_blocks(*span_id) {
-                let band_id = table.get(block_id).unwrap();
-                let band_name = live_info.band_name(*band_id);
-                string.push_str(&format!("{:?} ", band_name));
+        match self.format {
+            OutputFormat::Text => {
+                for span_index in 0..schedule.span_count() {
+                    let mut string = String::new();
+                    for entry in schedule.span(span_index) {
+                        let band_name = entry.band_id.and_then(|id| schedule.band_name(id));
+                        string.push_str(&format!("{:?} ", band_name.unwrap_or("")));
+                    }
+
+                    progress_bar.println(string);
+                }
+            }
+            OutputFormat::Csv => progress_bar.println(CsvTimetableExporter.export(schedule)),
+            OutputFormat::Markdown => {
+                progress_bar.println(MarkdownTimetableExporter.export(schedule))
+            }
+            OutputFormat::Html => {
+                progress_bar.println(HtmlTimetableExporter::new().export(schedule))
+            }
+            OutputFormat::Confluence => {
+                progress_bar.println(ConfluenceTimetableExporter.export(schedule))
             }
-
-            progress_bar.println(string);
         }
 
         self.finished_task_count += 1;
@@ -105,7 +142,10 @@ impl IScheduleCallback for ScheduleCallback {
     }
 
     fn on_completed(&mut self) {
-        self.progress_bar.as_mut().unwrap().finish();
+        // 探索を始める前に失敗した場合はプログレスバーがない
+        if let Some(progress_bar) = &self.progress_bar {
+            progress_bar.finish();
+        }
     }
 }
 
@@ -128,13 +168,51 @@ async fn run() {
     }
     let room_matrix = room_matrix_builder.build();
 
-    let live_info = kon_rs::algorithm::create_live_info(&band_table, &band_schedule, &room_matrix);
+    // スケジューリングの前に入力の問題をまとめて表示
+    let report = validate_live_info(&band_table, &band_schedule, &room_matrix);
+    for warning in &report.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    for error in &report.errors {
+        eprintln!("error: {}", error);
+    }
+    if !report.is_valid() {
+        return;
+    }
+
+    // 参加者一覧があればバンドの編成も確認
+    if let Some(roster_path) = &args.roster {
+        match std::fs::read_to_string(roster_path) {
+            Ok(data) => match parse_roster(&data, Strictness::Lenient) {
+                Ok(roster) => {
+                    for diagnostic in roster.diagnostics() {
+                        eprintln!("warning: {}: {}", roster_path, diagnostic);
+                    }
+                    let member_list = MemberList::new(roster.into_members());
+                    for warning in check_lineups(&band_table, &member_list) {
+                        eprintln!("warning: {}", warning);
+                    }
+                }
+                Err(error) => eprintln!("warning: {}: {}", roster_path, error),
+            },
+            Err(error) => eprintln!("warning: failed to read {}: {}", roster_path, error),
+        }
+    }
+
+    let live_info =
+        match kon_rs::algorithm::create_live_info(&band_table, &band_schedule, &room_matrix) {
+            Ok(live_info) => live_info,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                return;
+            }
+        };
     let live_info = Arc::new(live_info);
 
     // スケジュールを検索して...
-    let callback = ScheduleCallback::new();
+    let callback = ScheduleCallback::new(args.format);
     let mut scheduler = Scheduler::new_with_callback(callback);
-    if args.force_synchronize_for_debug {
+    let result = if args.force_synchronize_for_debug {
         // 同期実行
         scheduler.assign(&room_matrix, &live_info)
     } else {
@@ -146,7 +224,10 @@ async fn run() {
                 args.sub_tree_depth,
                 args.job_count,
             )
-            .await;
+            .await
+    };
+    if let Err(error) = result {
+        eprintln!("error: {}", error);
     }
 }
 
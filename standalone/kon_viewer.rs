@@ -60,13 +60,10 @@ impl<TClient: kon_players::clients::IClient + Default> eframe::App for App<TClie
     fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
         eframe::egui::CentralPanel::default().show(ctx, |ui| {
             // チェックボックスで担当楽器のフィルターを表示
-            let filter_list = vec![
-                ("Vocal", InstrumentType::VOCAL),
-                ("AcounsticGuitar", InstrumentType::ACOUSTIC_GUITAR),
-                ("ElectricGuitar", InstrumentType::ELECTRIC_GUITAR),
-                ("ElectricBass", InstrumentType::ELECTRIC_BASS),
-                ("Keyboard", InstrumentType::KEYBOARD),
-            ];
+            let filter_list: Vec<(String, InstrumentType)> = InstrumentType::all()
+                .iter()
+                .map(|instrument| (instrument.to_string(), instrument))
+                .collect();
             for item in filter_list {
                 let label = item.0;
                 let filter = item.1;